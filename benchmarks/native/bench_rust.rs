@@ -1,261 +1,7604 @@
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::env;
-use std::time::Instant;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-#[derive(Clone, Copy)]
+// Diagnostics go to stderr through these macros; results only ever go to the
+// selected output, so the two streams never mix.
+macro_rules! error {
+    ($($arg:tt)*) => { log_message(LogLevel::Error, format_args!($($arg)*)) };
+}
+
+macro_rules! warn {
+    ($($arg:tt)*) => { log_message(LogLevel::Warn, format_args!($($arg)*)) };
+}
+
+// A warning about the quality of the results, fatal under --strict.
+macro_rules! strict_warn {
+    ($($arg:tt)*) => { strict_warning(format_args!($($arg)*)) };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { log_message(LogLevel::Info, format_args!($($arg)*)) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { log_message(LogLevel::Debug, format_args!($($arg)*)) };
+}
+
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    fn name(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warning",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+}
+
+static LOG_LEVEL: AtomicUsize = AtomicUsize::new(LogLevel::Warn as usize);
+
+fn log_message(level: LogLevel, message: std::fmt::Arguments) {
+    if level as usize <= LOG_LEVEL.load(Ordering::Relaxed) {
+        eprintln!("{}: {}", level.label(), message);
+    }
+}
+
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+fn strict_warning(message: std::fmt::Arguments) {
+    if STRICT.load(Ordering::Relaxed) {
+        log_message(LogLevel::Error, message);
+        error!("stopping because --strict is set.");
+        std::process::exit(1);
+    }
+    log_message(LogLevel::Warn, message);
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum OutputFormat {
+    Csv,
+    Tsv,
+    Table,
+    Influx,
+    Json,
+    Ndjson,
+    Gnuplot,
+    Markdown,
+}
+
+impl OutputFormat {
+    fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+            OutputFormat::Table => "table",
+            OutputFormat::Influx => "influx",
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Gnuplot => "gnuplot",
+            OutputFormat::Markdown => "markdown",
+        }
+    }
+
+    // The extension --output-dir gives this format's file.
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+            OutputFormat::Table => "txt",
+            OutputFormat::Influx => "lp",
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Gnuplot => "dat",
+            OutputFormat::Markdown => "md",
+        }
+    }
+}
+
+const OUTPUT_FORMATS: [OutputFormat; 8] = [
+    OutputFormat::Csv,
+    OutputFormat::Tsv,
+    OutputFormat::Table,
+    OutputFormat::Influx,
+    OutputFormat::Json,
+    OutputFormat::Ndjson,
+    OutputFormat::Gnuplot,
+    OutputFormat::Markdown,
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum DotOrder {
+    Serial,
+    Pairwise,
+}
+
+impl DotOrder {
+    fn name(self) -> &'static str {
+        match self {
+            DotOrder::Serial => "serial",
+            DotOrder::Pairwise => "pairwise",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Layout {
+    Soa,
+    Aos,
+}
+
+impl Layout {
+    fn name(self) -> &'static str {
+        match self {
+            Layout::Soa => "soa",
+            Layout::Aos => "aos",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum ClosureMode {
+    Boxed,
+    Static,
+}
+
+impl ClosureMode {
+    fn name(self) -> &'static str {
+        match self {
+            ClosureMode::Boxed => "boxed",
+            ClosureMode::Static => "static",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum RcMode {
+    Rc,
+    Arc,
+}
+
+impl RcMode {
+    fn name(self) -> &'static str {
+        match self {
+            RcMode::Rc => "rc",
+            RcMode::Arc => "arc",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum BoundsMode {
+    Checked,
+    Unchecked,
+}
+
+impl BoundsMode {
+    fn name(self) -> &'static str {
+        match self {
+            BoundsMode::Checked => "checked",
+            BoundsMode::Unchecked => "unchecked",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+}
+
+#[derive(Clone)]
 struct BenchmarkOptions {
     iterations: usize,
     sum_n: u64,
     prime_n: usize,
     matrix_n: usize,
+    pairs_n: usize,
+    div_n: u64,
+    dot_n: usize,
+    dot_order: DotOrder,
+    atomic_n: u64,
+    threads: usize,
+    strmap_n: usize,
+    rle_n: usize,
+    parse_n: usize,
+    heap_n: usize,
+    copy_kb: usize,
+    copy_reps: usize,
+    newton_n: usize,
+    bitset_n: usize,
+    vm_mix_n: usize,
+    vm_arith_pct: u64,
+    bfs_nodes: usize,
+    bfs_degree: usize,
+    bst_n: usize,
+    sat_n: usize,
+    layout_n: usize,
+    layout: Layout,
+    scan_n: usize,
+    b64_n: usize,
+    rng: RngKind,
+    err_n: usize,
+    err_rate: f64,
+    bodies: usize,
+    steps: usize,
+    closure_n: usize,
+    closure: ClosureMode,
+    flood_n: usize,
+    iter_n: u64,
+    lcs_n: usize,
+    tail_n: u64,
+    gap_n: usize,
+    tok_n: usize,
+    bignum_digits: usize,
+    bignum_reps: usize,
+    set_n: usize,
+    regex_n: usize,
+    vtable_n: usize,
+    dij_nodes: usize,
+    dij_degree: usize,
+    horner_degree: usize,
+    horner_reps: usize,
+    rc_n: usize,
+    rc: RcMode,
+    matvec_n: usize,
+    matvec_reps: usize,
+    topo_nodes: usize,
+    topo_edges: usize,
+    edfs_n: usize,
+    merkle_leaves: usize,
+    life_n: usize,
+    life_steps: usize,
+    array_n: usize,
+    bounds: BoundsMode,
+    hist_n: usize,
+    hist_buckets: usize,
+    strbuild_n: usize,
+    match_n: usize,
+    bsearch_n: usize,
+    bsearch_queries: usize,
+    clone_depth: usize,
+    clone_width: usize,
+    clone_reps: usize,
+    edit_n: usize,
+    expr_n: usize,
+    gc_objects: usize,
+    gc_refs: usize,
+    seed: u64,
+    seed_sweep: usize,
+    repeat: usize,
+    size_range: Option<(u64, u64)>,
+    size_kernel: Option<String>,
+    repeat_until_stable: bool,
+    stable_threshold: f64,
+    max_batches: usize,
+    shuffle_order: bool,
+    shuffle_seed: u64,
+    per_iteration: bool,
+    perf_counters: bool,
+    parameters_hash: bool,
+    self_audit: bool,
+    self_audit_tolerance: f64,
+    drift_report: bool,
+    ramp_report: bool,
+    profile_hot: bool,
+    drift_threshold: f64,
+    strict: bool,
+    samples_out: Option<String>,
+    format: OutputFormat,
+    human_numbers: bool,
+    no_header: bool,
+    csv_quote_always: bool,
+    columns: Option<String>,
+    summary_line: bool,
+    annotate: bool,
+    output: Option<String>,
+    tee: Option<String>,
+    output_dir: Option<String>,
+    append: bool,
+    resume: Option<String>,
+    against_last: Option<String>,
+    baseline: Option<String>,
+    geomean: bool,
+    trend: Option<String>,
+    trend_threshold: f64,
+    since: Option<u64>,
+    verify: Option<String>,
+    verify_tolerance: Option<u64>,
+    fail_fast: bool,
+    write_reference: Option<String>,
+    self_verify: bool,
+    fairness_check: bool,
+    compare_cmd: Option<String>,
+    yes: bool,
+    log_level: LogLevel,
+    print_config: bool,
+    emit_env: bool,
+    numa_node: Option<usize>,
+    init_delay_ms: u64,
+    cooldown_ms: u64,
+    completions: Option<Shell>,
+    manifest: bool,
+}
+
+impl BenchmarkOptions {
+    // Diagnostics that need per-iteration durations turn collection on even
+    // without --per-iteration; they just do not add its columns.
+    fn collects_samples(&self) -> bool {
+        self.per_iteration
+            || self.self_audit
+            || self.drift_report
+            || self.ramp_report
+            || self.samples_out.is_some()
+    }
+
+    // Every field, defaults included, in declaration order.
+    fn to_json(&self) -> JsonObject {
+        let mut json = JsonObject::new();
+        json.number("iterations", self.iterations);
+        json.number("sum_n", self.sum_n);
+        json.number("prime_n", self.prime_n);
+        json.number("matrix_n", self.matrix_n);
+        json.number("pairs_n", self.pairs_n);
+        json.number("div_n", self.div_n);
+        json.number("dot_n", self.dot_n);
+        json.string("dot_order", self.dot_order.name());
+        json.number("atomic_n", self.atomic_n);
+        json.number("threads", self.threads);
+        json.number("strmap_n", self.strmap_n);
+        json.number("rle_n", self.rle_n);
+        json.number("parse_n", self.parse_n);
+        json.number("heap_n", self.heap_n);
+        json.number("copy_kb", self.copy_kb);
+        json.number("copy_reps", self.copy_reps);
+        json.number("newton_n", self.newton_n);
+        json.number("bitset_n", self.bitset_n);
+        json.number("vm_mix_n", self.vm_mix_n);
+        json.number("vm_arith_pct", self.vm_arith_pct);
+        json.number("bfs_nodes", self.bfs_nodes);
+        json.number("bfs_degree", self.bfs_degree);
+        json.number("bst_n", self.bst_n);
+        json.number("sat_n", self.sat_n);
+        json.number("layout_n", self.layout_n);
+        json.string("layout", self.layout.name());
+        json.number("scan_n", self.scan_n);
+        json.number("b64_n", self.b64_n);
+        json.string("rng", self.rng.name());
+        json.number("err_n", self.err_n);
+        json.number("err_rate", self.err_rate);
+        json.number("bodies", self.bodies);
+        json.number("steps", self.steps);
+        json.number("closure_n", self.closure_n);
+        json.string("closure", self.closure.name());
+        json.number("flood_n", self.flood_n);
+        json.number("iter_n", self.iter_n);
+        json.number("lcs_n", self.lcs_n);
+        json.number("tail_n", self.tail_n);
+        json.number("gap_n", self.gap_n);
+        json.number("tok_n", self.tok_n);
+        json.number("bignum_digits", self.bignum_digits);
+        json.number("bignum_reps", self.bignum_reps);
+        json.number("set_n", self.set_n);
+        json.number("regex_n", self.regex_n);
+        json.number("vtable_n", self.vtable_n);
+        json.number("dij_nodes", self.dij_nodes);
+        json.number("dij_degree", self.dij_degree);
+        json.number("horner_degree", self.horner_degree);
+        json.number("horner_reps", self.horner_reps);
+        json.number("rc_n", self.rc_n);
+        json.string("rc", self.rc.name());
+        json.number("matvec_n", self.matvec_n);
+        json.number("matvec_reps", self.matvec_reps);
+        json.number("topo_nodes", self.topo_nodes);
+        json.number("topo_edges", self.topo_edges);
+        json.number("edfs_n", self.edfs_n);
+        json.number("merkle_leaves", self.merkle_leaves);
+        json.number("life_n", self.life_n);
+        json.number("life_steps", self.life_steps);
+        json.number("array_n", self.array_n);
+        json.string("bounds", self.bounds.name());
+        json.number("hist_n", self.hist_n);
+        json.number("hist_buckets", self.hist_buckets);
+        json.number("strbuild_n", self.strbuild_n);
+        json.number("match_n", self.match_n);
+        json.number("bsearch_n", self.bsearch_n);
+        json.number("bsearch_queries", self.bsearch_queries);
+        json.number("clone_depth", self.clone_depth);
+        json.number("clone_width", self.clone_width);
+        json.number("clone_reps", self.clone_reps);
+        json.number("edit_n", self.edit_n);
+        json.number("expr_n", self.expr_n);
+        json.number("gc_objects", self.gc_objects);
+        json.number("gc_refs", self.gc_refs);
+        json.number("seed", self.seed);
+        json.number("seed_sweep", self.seed_sweep);
+        json.number("repeat", self.repeat);
+        let size_range = self.size_range.map(|(low, high)| format!("{} {}", low, high));
+        json.optional_string("size_range", size_range.as_deref());
+        json.optional_string("size_kernel", self.size_kernel.as_deref());
+        json.boolean("repeat_until_stable", self.repeat_until_stable);
+        json.number("stable_threshold", self.stable_threshold);
+        json.number("max_batches", self.max_batches);
+        json.boolean("shuffle_order", self.shuffle_order);
+        json.number("shuffle_seed", self.shuffle_seed);
+        json.boolean("per_iteration", self.per_iteration);
+        json.boolean("perf_counters", self.perf_counters);
+        json.boolean("parameters_hash", self.parameters_hash);
+        json.boolean("self_audit", self.self_audit);
+        json.number("self_audit_tolerance", self.self_audit_tolerance);
+        json.boolean("drift_report", self.drift_report);
+        json.boolean("ramp_report", self.ramp_report);
+        json.boolean("profile_hot", self.profile_hot);
+        json.number("drift_threshold", self.drift_threshold);
+        json.boolean("strict", self.strict);
+        json.optional_string("samples_out", self.samples_out.as_deref());
+        json.string("format", self.format.name());
+        json.boolean("human_numbers", self.human_numbers);
+        json.boolean("no_header", self.no_header);
+        json.boolean("csv_quote_always", self.csv_quote_always);
+        json.optional_string("columns", self.columns.as_deref());
+        json.boolean("summary_line", self.summary_line);
+        json.boolean("annotate", self.annotate);
+        json.optional_string("output", self.output.as_deref());
+        json.optional_string("tee", self.tee.as_deref());
+        json.optional_string("output_dir", self.output_dir.as_deref());
+        json.boolean("append", self.append);
+        json.optional_string("resume", self.resume.as_deref());
+        json.optional_string("against_last", self.against_last.as_deref());
+        json.optional_string("baseline", self.baseline.as_deref());
+        json.optional_string("trend", self.trend.as_deref());
+        json.number("trend_threshold", self.trend_threshold);
+        json.optional_string("since", self.since.map(iso8601_from_unix).as_deref());
+        json.boolean("geomean", self.geomean);
+        json.optional_string("verify", self.verify.as_deref());
+        json.optional_number("verify_tolerance", self.verify_tolerance);
+        json.boolean("fail_fast", self.fail_fast);
+        json.optional_string("write_reference", self.write_reference.as_deref());
+        json.boolean("self_verify", self.self_verify);
+        json.boolean("fairness_check", self.fairness_check);
+        json.optional_string("compare_cmd", self.compare_cmd.as_deref());
+        json.boolean("yes", self.yes);
+        json.string("log_level", self.log_level.name());
+        json.boolean("print_config", self.print_config);
+        json.boolean("emit_env", self.emit_env);
+        json.optional_number("numa_node", self.numa_node);
+        json.number("init_delay_ms", self.init_delay_ms);
+        json.number("cooldown_ms", self.cooldown_ms);
+        json.optional_string("completions", self.completions.map(Shell::name));
+        json.boolean("manifest", self.manifest);
+        json
+    }
+}
+
+fn json_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+// A JSON object that keeps its keys in insertion order.
+struct JsonObject {
+    fields: Vec<(&'static str, String)>,
+}
+
+impl JsonObject {
+    fn new() -> JsonObject {
+        JsonObject { fields: Vec::new() }
+    }
+
+    fn string(&mut self, key: &'static str, value: &str) {
+        self.fields.push((key, json_escape(value)));
+    }
+
+    fn optional_string(&mut self, key: &'static str, value: Option<&str>) {
+        let rendered = value.map_or_else(|| "null".to_string(), json_escape);
+        self.fields.push((key, rendered));
+    }
+
+    fn number<T: std::fmt::Display>(&mut self, key: &'static str, value: T) {
+        self.fields.push((key, value.to_string()));
+    }
+
+    fn optional_number<T: std::fmt::Display>(&mut self, key: &'static str, value: Option<T>) {
+        let rendered = value.map_or_else(|| "null".to_string(), |value| value.to_string());
+        self.fields.push((key, rendered));
+    }
+
+    fn boolean(&mut self, key: &'static str, value: bool) {
+        self.fields.push((key, value.to_string()));
+    }
+
+    fn string_array(&mut self, key: &'static str, values: &[&str]) {
+        let items: Vec<String> = values.iter().map(|value| json_escape(value)).collect();
+        self.fields.push((key, format!("[{}]", items.join(", "))));
+    }
+
+    fn to_pretty_string(&self) -> String {
+        if self.fields.is_empty() {
+            return "{}".to_string();
+        }
+
+        let lines: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(key, value)| format!("  {}: {}", json_escape(key), value))
+            .collect();
+        format!("{{\n{}\n}}", lines.join(",\n"))
+    }
+
+    fn object_array(&mut self, key: &'static str, objects: &[JsonObject]) {
+        let items: Vec<String> = objects
+            .iter()
+            .map(|object| format!("    {}", object.to_compact_string()))
+            .collect();
+        self.fields.push((key, format!("[\n{}\n  ]", items.join(",\n"))));
+    }
+
+    fn to_compact_string(&self) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(key, value)| format!("{}: {}", json_escape(key), value))
+            .collect();
+        format!("{{{}}}", fields.join(", "))
+    }
+}
+
+// A seed that differs between runs, for options whose seed is reported
+// rather than fixed.
+fn clock_seed() -> u64 {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    (elapsed.as_nanos() % 2_147_483_647) as u64
+}
+
+fn parse_value<T: FromStr>(args: &mut impl Iterator<Item = String>, flag: &str) -> T {
+    let value = args
+        .next()
+        .unwrap_or_else(|| panic!("Missing value for {}.", flag));
+    value
+        .parse()
+        .unwrap_or_else(|_| panic!("Invalid value for {}.", flag))
+}
+
+#[derive(Clone, Copy)]
+enum ArgKind {
+    Flag,
+    Value,
+    // Two values, such as `--size-range <lo> <hi>`.
+    Pair,
+    Path,
+    Choice(&'static [&'static str]),
+}
+
+struct CliOption {
+    long: &'static str,
+    short: Option<&'static str>,
+    kind: ArgKind,
+}
+
+// Every flag the harness accepts. parse_options rejects anything not listed
+// here, and --completions is generated from it, so a new option has to be
+// added to this table to be usable at all.
+const CLI_OPTIONS: &[CliOption] = &[
+    CliOption {
+        long: "--iterations",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--sum-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--prime-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--sieve-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--matrix-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--pairs-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--div-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--dot-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--dot-order",
+        short: None,
+        kind: ArgKind::Choice(&["serial", "pairwise"]),
+    },
+    CliOption {
+        long: "--atomic-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--threads",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--strmap-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--rle-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--parse-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--heap-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--copy-kb",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--copy-reps",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--newton-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--bitset-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--vm-mix-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--vm-arith-pct",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--bfs-nodes",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--bfs-degree",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--bst-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--sat-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--layout-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--layout",
+        short: None,
+        kind: ArgKind::Choice(&["soa", "aos"]),
+    },
+    CliOption {
+        long: "--scan-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--b64-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--rng",
+        short: None,
+        kind: ArgKind::Choice(&["lcg", "xorshift", "splitmix64"]),
+    },
+    CliOption {
+        long: "--err-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--err-rate",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--bodies",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--steps",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--closure-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--closure",
+        short: None,
+        kind: ArgKind::Choice(&["boxed", "static"]),
+    },
+    CliOption {
+        long: "--flood-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--iter-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--lcs-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--tail-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--gap-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--tok-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--bignum-digits",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--bignum-reps",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--set-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--regex-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--vtable-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--dij-nodes",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--dij-degree",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--horner-degree",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--horner-reps",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--rc-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--rc",
+        short: None,
+        kind: ArgKind::Choice(&["rc", "arc"]),
+    },
+    CliOption {
+        long: "--matvec-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--matvec-reps",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--topo-nodes",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--topo-edges",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--edfs-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--merkle-leaves",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--life-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--life-steps",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--array-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--bounds",
+        short: None,
+        kind: ArgKind::Choice(&["checked", "unchecked"]),
+    },
+    CliOption {
+        long: "--hist-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--hist-buckets",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--strbuild-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--match-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--bsearch-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--bsearch-queries",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--clone-depth",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--clone-width",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--clone-reps",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--edit-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--expr-n",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--gc-objects",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--gc-refs",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--seed",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--seed-sweep",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--repeat",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--size-range",
+        short: None,
+        kind: ArgKind::Pair,
+    },
+    CliOption {
+        long: "--size-kernel",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--repeat-until-stable",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--stable-threshold",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--max-batches",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--shuffle-order",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--shuffle-seed",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--per-iteration",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--perf-counters",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--parameters-hash",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--self-audit",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--self-audit-tolerance",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--drift-report",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--ramp-report",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--profile-hot",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--drift-threshold",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--strict",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--samples-out",
+        short: None,
+        kind: ArgKind::Path,
+    },
+    CliOption {
+        long: "--format",
+        short: None,
+        kind: ArgKind::Choice(&["csv", "tsv", "table", "influx", "json", "ndjson", "gnuplot", "markdown"]),
+    },
+    CliOption {
+        long: "--human-numbers",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--no-header",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--csv-quote-always",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--columns",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--summary-line",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--annotate",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--output",
+        short: None,
+        kind: ArgKind::Path,
+    },
+    CliOption {
+        long: "--tee",
+        short: None,
+        kind: ArgKind::Path,
+    },
+    CliOption {
+        long: "--output-dir",
+        short: None,
+        kind: ArgKind::Path,
+    },
+    CliOption {
+        long: "--append",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--resume",
+        short: None,
+        kind: ArgKind::Path,
+    },
+    CliOption {
+        long: "--against-last",
+        short: None,
+        kind: ArgKind::Path,
+    },
+    CliOption {
+        long: "--baseline",
+        short: None,
+        kind: ArgKind::Path,
+    },
+    CliOption {
+        long: "--geomean",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--trend",
+        short: None,
+        kind: ArgKind::Path,
+    },
+    CliOption {
+        long: "--trend-threshold",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--since",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--verify",
+        short: None,
+        kind: ArgKind::Path,
+    },
+    CliOption {
+        long: "--verify-tolerance",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--fail-fast",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--write-reference",
+        short: None,
+        kind: ArgKind::Path,
+    },
+    CliOption {
+        long: "--self-verify",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--fairness-check",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--compare-cmd",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--yes",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--log-level",
+        short: None,
+        kind: ArgKind::Choice(&["error", "warn", "info", "debug"]),
+    },
+    CliOption {
+        long: "--verbose",
+        short: Some("-v"),
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--quiet",
+        short: Some("-q"),
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--print-config",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--emit-env",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--numa-node",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--init-delay-ms",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--cooldown-ms",
+        short: None,
+        kind: ArgKind::Value,
+    },
+    CliOption {
+        long: "--manifest",
+        short: None,
+        kind: ArgKind::Flag,
+    },
+    CliOption {
+        long: "--completions",
+        short: None,
+        kind: ArgKind::Choice(&["bash", "zsh", "fish"]),
+    },
+];
+
+// Debugging aids that parse like any other option but stay out of the
+// completions, the environment fallbacks, and the README.
+// `--emit-all-formats <dir>` is an alias for `--output-dir <dir>`.
+const HIDDEN_OPTIONS: &[CliOption] = &[CliOption {
+    long: "--emit-all-formats",
+    short: None,
+    kind: ArgKind::Path,
+}];
+
+fn find_cli_option(arg: &str) -> Option<&'static CliOption> {
+    CLI_OPTIONS
+        .iter()
+        .chain(HIDDEN_OPTIONS)
+        .find(|option| option.long == arg || option.short == Some(arg))
+}
+
+const COMMAND_NAME: &str = "bench_rust";
+const ENV_PREFIX: &str = "OAFBENCH_";
+
+// `--shuffle-seed` is read from `OAFBENCH_SHUFFLE_SEED`.
+fn env_name(option: &CliOption) -> String {
+    format!("{}{}", ENV_PREFIX, option.long.trim_start_matches("--").replace('-', "_").to_uppercase())
+}
+
+// `--<flag>=1`/`=true` on the command line is the flag itself, and
+// `--<flag>=0`/`=false` keeps it off even when its environment variable
+// sets it. Returns None for every other argument.
+fn flag_setting(arg: &str) -> Option<(&'static CliOption, bool)> {
+    let (name, value) = arg.split_once('=')?;
+    let option = find_cli_option(name).filter(|option| matches!(option.kind, ArgKind::Flag))?;
+    match value {
+        "1" | "true" => Some((option, true)),
+        "0" | "false" => Some((option, false)),
+        _ => panic!("Invalid value for {}. Use '1' or '0'.", name),
+    }
+}
+
+// Turns the OAFBENCH_* variables that are set into command-line arguments,
+// in CLI_OPTIONS order. parse_options reads them before the real arguments
+// `cli`, so they go through the same parsing and checks and a value on the
+// command line overrides one from the environment. A flag variable is
+// `1`/`true` to set it and `0`/`false` or empty to leave it off, and is
+// skipped when `cli` turns the flag off with `--<flag>=0`; a two-value
+// option takes both values separated by a space.
+fn env_args(cli: &[String]) -> Vec<String> {
+    let mut args = Vec::new();
+    for option in CLI_OPTIONS {
+        let turned_off = cli
+            .iter()
+            .any(|arg| flag_setting(arg).is_some_and(|(flag, on)| flag.long == option.long && !on));
+        if turned_off {
+            continue;
+        }
+        let name = env_name(option);
+        let value = match env::var(&name) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        match option.kind {
+            ArgKind::Flag => match value.as_str() {
+                "1" | "true" => args.push(option.long.to_string()),
+                "0" | "false" | "" => {}
+                _ => panic!("Invalid value for {}. Use '1' or '0'.", name),
+            },
+            ArgKind::Pair => {
+                let values: Vec<&str> = value.split_whitespace().collect();
+                if values.len() != 2 {
+                    panic!("Invalid value for {}. Use two values separated by a space.", name);
+                }
+                args.push(option.long.to_string());
+                args.extend(values.into_iter().map(str::to_string));
+            }
+            _ => {
+                args.push(option.long.to_string());
+                args.push(value);
+            }
+        }
+    }
+    args
+}
+
+// A completion script for `shell` covering every entry of CLI_OPTIONS:
+// choices complete their values, paths complete file names, and other
+// values are left to the user.
+fn completion_script(shell: Shell) -> String {
+    let mut script = String::new();
+    match shell {
+        Shell::Bash => {
+            let function = format!("_{}", COMMAND_NAME);
+            let words: Vec<&str> = CLI_OPTIONS
+                .iter()
+                .flat_map(|option| std::iter::once(option.long).chain(option.short))
+                .collect();
+            script.push_str(&format!("{}() {{\n", function));
+            script.push_str("    local cur=\"${COMP_WORDS[COMP_CWORD]}\" prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+            script.push_str("    case \"$prev\" in\n");
+            for option in CLI_OPTIONS {
+                let action = match option.kind {
+                    ArgKind::Flag => continue,
+                    ArgKind::Value | ArgKind::Pair => "return".to_string(),
+                    ArgKind::Path => "COMPREPLY=($(compgen -f -- \"$cur\")); return".to_string(),
+                    ArgKind::Choice(values) => format!(
+                        "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); return",
+                        values.join(" ")
+                    ),
+                };
+                script.push_str(&format!("        {}) {} ;;\n", option.long, action));
+            }
+            script.push_str("    esac\n");
+            script.push_str(&format!(
+                "    COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n}}\n",
+                words.join(" ")
+            ));
+            script.push_str(&format!("complete -F {} {}\n", function, COMMAND_NAME));
+        }
+        Shell::Zsh => {
+            script.push_str(&format!("#compdef {}\n\n_arguments \\\n", COMMAND_NAME));
+            for option in CLI_OPTIONS {
+                let action = match option.kind {
+                    ArgKind::Flag => String::new(),
+                    ArgKind::Value => ":value: ".to_string(),
+                    ArgKind::Pair => ":low: :high: ".to_string(),
+                    ArgKind::Path => ":path:_files".to_string(),
+                    ArgKind::Choice(values) => format!(":value:({})", values.join(" ")),
+                };
+                for name in std::iter::once(option.long).chain(option.short) {
+                    script.push_str(&format!("  '{}{}' \\\n", name, action));
+                }
+            }
+            script.push_str("  && return 0\n");
+        }
+        Shell::Fish => {
+            for option in CLI_OPTIONS {
+                let mut line = format!("complete -c {} -l {}", COMMAND_NAME, &option.long[2..]);
+                if let Some(short) = option.short {
+                    line.push_str(&format!(" -s {}", &short[1..]));
+                }
+                match option.kind {
+                    ArgKind::Flag => {}
+                    ArgKind::Value | ArgKind::Pair => line.push_str(" -x"),
+                    ArgKind::Path => line.push_str(" -r -F"),
+                    ArgKind::Choice(values) => line.push_str(&format!(" -x -a \"{}\"", values.join(" "))),
+                }
+                script.push_str(&line);
+                script.push('\n');
+            }
+        }
+    }
+    script
+}
+
+fn default_options() -> BenchmarkOptions {
+    BenchmarkOptions {
+        iterations: 5,
+        sum_n: 5_000_000,
+        prime_n: 30_000,
+        matrix_n: 48,
+        pairs_n: 100_000,
+        div_n: 2_000_000,
+        dot_n: 200_000,
+        dot_order: DotOrder::Serial,
+        atomic_n: 200_000,
+        threads: 4,
+        strmap_n: 20_000,
+        rle_n: 1_000_000,
+        parse_n: 200_000,
+        heap_n: 50_000,
+        copy_kb: 1024,
+        copy_reps: 100,
+        newton_n: 50_000,
+        bitset_n: 1_000_000,
+        vm_mix_n: 2_000_000,
+        vm_arith_pct: 70,
+        bfs_nodes: 20_000,
+        bfs_degree: 4,
+        bst_n: 20_000,
+        sat_n: 1_000_000,
+        layout_n: 200_000,
+        layout: Layout::Soa,
+        scan_n: 1_000_000,
+        b64_n: 1_000_000,
+        rng: RngKind::Lcg,
+        err_n: 1_000_000,
+        err_rate: 0.1,
+        bodies: 10_000,
+        steps: 100,
+        closure_n: 2_000_000,
+        closure: ClosureMode::Boxed,
+        flood_n: 500,
+        iter_n: 5_000_000,
+        lcs_n: 1_500,
+        tail_n: 5_000_000,
+        gap_n: 20_000,
+        tok_n: 200_000,
+        bignum_digits: 10_000,
+        bignum_reps: 200,
+        set_n: 200_000,
+        regex_n: 50_000,
+        vtable_n: 5_000_000,
+        dij_nodes: 20_000,
+        dij_degree: 4,
+        horner_degree: 64,
+        horner_reps: 100_000,
+        rc_n: 1_000_000,
+        rc: RcMode::Rc,
+        matvec_n: 1_000,
+        matvec_reps: 10,
+        topo_nodes: 20_000,
+        topo_edges: 80_000,
+        edfs_n: 200_000,
+        merkle_leaves: 1_000_000,
+        life_n: 256,
+        life_steps: 20,
+        array_n: 100_000,
+        bounds: BoundsMode::Checked,
+        hist_n: 1_000_000,
+        hist_buckets: 4096,
+        strbuild_n: 500_000,
+        match_n: 5_000_000,
+        bsearch_n: 100_000,
+        bsearch_queries: 200_000,
+        clone_depth: 6,
+        clone_width: 4,
+        clone_reps: 200,
+        edit_n: 1_500,
+        expr_n: 30_000,
+        gc_objects: 200_000,
+        gc_refs: 4,
+        seed: DEFAULT_SEED,
+        seed_sweep: 1,
+        repeat: 1,
+        size_range: None,
+        size_kernel: None,
+        repeat_until_stable: false,
+        stable_threshold: 1.0,
+        max_batches: 20,
+        shuffle_order: false,
+        shuffle_seed: clock_seed(),
+        per_iteration: false,
+        perf_counters: false,
+        parameters_hash: false,
+        self_audit: false,
+        self_audit_tolerance: 5.0,
+        drift_report: false,
+        ramp_report: false,
+        profile_hot: false,
+        drift_threshold: 10.0,
+        strict: false,
+        samples_out: None,
+        format: OutputFormat::Csv,
+        human_numbers: false,
+        no_header: false,
+        csv_quote_always: false,
+        columns: None,
+        summary_line: false,
+        annotate: false,
+        output: None,
+        tee: None,
+        output_dir: None,
+        append: false,
+        resume: None,
+        against_last: None,
+        baseline: None,
+        trend: None,
+        trend_threshold: 10.0,
+        since: None,
+        geomean: false,
+        verify: None,
+        verify_tolerance: None,
+        fail_fast: false,
+        write_reference: None,
+        self_verify: false,
+        fairness_check: false,
+        compare_cmd: None,
+        yes: false,
+        log_level: LogLevel::Warn,
+        print_config: false,
+        emit_env: false,
+        numa_node: None,
+        init_delay_ms: 0,
+        cooldown_ms: 0,
+        completions: None,
+        manifest: false,
+    }
+}
+
+// The format implied by an --output path's extension, for runs without
+// --format: the format whose --output-dir file has that extension.
+// Unrecognized extensions fall back to CSV.
+fn format_for_path(path: &str) -> OutputFormat {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    let extension = match extension {
+        Some(extension) => extension,
+        None => return OutputFormat::Csv,
+    };
+    match OUTPUT_FORMATS.iter().find(|format| format.extension() == extension) {
+        Some(format) => *format,
+        None => {
+            warn!("no output format for '.{}' files; writing {} as CSV (pass --format to choose).", extension, path);
+            OutputFormat::Csv
+        }
+    }
+}
+
+fn parse_options() -> BenchmarkOptions {
+    let mut options = default_options();
+
+    let mut shuffle_seed_given = false;
+    let mut iterations_given = false;
+    let mut repeat_given = false;
+    let mut format_given = false;
+    let cli: Vec<String> = env::args().skip(1).collect();
+    let mut args = env_args(&cli).into_iter().chain(cli.into_iter().filter_map(|arg| match flag_setting(&arg) {
+        Some((flag, true)) => Some(flag.long.to_string()),
+        Some((_, false)) => None,
+        None => Some(arg),
+    }));
+    while let Some(arg) = args.next() {
+        if find_cli_option(&arg).is_none() {
+            panic!("Unknown option '{}'.", arg);
+        }
+        match arg.as_str() {
+            "--iterations" => {
+                options.iterations = parse_value(&mut args, "--iterations");
+                iterations_given = true;
+            }
+            "--sum-n" => {
+                options.sum_n = parse_value(&mut args, "--sum-n");
+            }
+            "--prime-n" | "--sieve-n" => {
+                options.prime_n = parse_value(&mut args, "--prime-n");
+            }
+            "--matrix-n" => {
+                options.matrix_n = parse_value(&mut args, "--matrix-n");
+            }
+            "--pairs-n" => {
+                options.pairs_n = parse_value(&mut args, "--pairs-n");
+            }
+            "--div-n" => {
+                options.div_n = parse_value(&mut args, "--div-n");
+            }
+            "--dot-n" => {
+                options.dot_n = parse_value(&mut args, "--dot-n");
+            }
+            "--dot-order" => {
+                let value = args.next().expect("Missing value for --dot-order.");
+                options.dot_order = match value.as_str() {
+                    "serial" => DotOrder::Serial,
+                    "pairwise" => DotOrder::Pairwise,
+                    _ => panic!("Invalid value for --dot-order. Use 'serial' or 'pairwise'."),
+                };
+            }
+            "--atomic-n" => {
+                options.atomic_n = parse_value(&mut args, "--atomic-n");
+            }
+            "--threads" => {
+                options.threads = parse_value(&mut args, "--threads");
+            }
+            "--strmap-n" => {
+                options.strmap_n = parse_value(&mut args, "--strmap-n");
+            }
+            "--rle-n" => {
+                options.rle_n = parse_value(&mut args, "--rle-n");
+            }
+            "--parse-n" => {
+                options.parse_n = parse_value(&mut args, "--parse-n");
+            }
+            "--heap-n" => {
+                options.heap_n = parse_value(&mut args, "--heap-n");
+            }
+            "--copy-kb" => {
+                options.copy_kb = parse_value(&mut args, "--copy-kb");
+            }
+            "--copy-reps" => {
+                options.copy_reps = parse_value(&mut args, "--copy-reps");
+            }
+            "--newton-n" => {
+                options.newton_n = parse_value(&mut args, "--newton-n");
+            }
+            "--bitset-n" => {
+                options.bitset_n = parse_value(&mut args, "--bitset-n");
+            }
+            "--vm-mix-n" => {
+                options.vm_mix_n = parse_value(&mut args, "--vm-mix-n");
+            }
+            "--vm-arith-pct" => {
+                options.vm_arith_pct = parse_value(&mut args, "--vm-arith-pct");
+                if options.vm_arith_pct > 100 {
+                    panic!("Invalid value for --vm-arith-pct. Use a percentage from 0 to 100.");
+                }
+            }
+            "--bfs-nodes" => {
+                options.bfs_nodes = parse_value(&mut args, "--bfs-nodes");
+            }
+            "--bfs-degree" => {
+                options.bfs_degree = parse_value(&mut args, "--bfs-degree");
+            }
+            "--bst-n" => {
+                options.bst_n = parse_value(&mut args, "--bst-n");
+            }
+            "--sat-n" => {
+                options.sat_n = parse_value(&mut args, "--sat-n");
+            }
+            "--layout-n" => {
+                options.layout_n = parse_value(&mut args, "--layout-n");
+            }
+            "--layout" => {
+                let value = args.next().expect("Missing value for --layout.");
+                options.layout = match value.as_str() {
+                    "soa" => Layout::Soa,
+                    "aos" => Layout::Aos,
+                    _ => panic!("Invalid value for --layout. Use 'soa' or 'aos'."),
+                };
+            }
+            "--scan-n" => {
+                options.scan_n = parse_value(&mut args, "--scan-n");
+            }
+            "--b64-n" => {
+                options.b64_n = parse_value(&mut args, "--b64-n");
+            }
+            "--rng" => {
+                let value = args.next().expect("Missing value for --rng.");
+                options.rng = match value.as_str() {
+                    "lcg" => RngKind::Lcg,
+                    "xorshift" => RngKind::Xorshift,
+                    "splitmix64" => RngKind::Splitmix64,
+                    _ => panic!("Invalid value for --rng. Use 'lcg', 'xorshift', or 'splitmix64'."),
+                };
+            }
+            "--err-n" => {
+                options.err_n = parse_value(&mut args, "--err-n");
+            }
+            "--err-rate" => {
+                options.err_rate = parse_value(&mut args, "--err-rate");
+                if !(0.0..=1.0).contains(&options.err_rate) {
+                    panic!("Invalid value for --err-rate. Use a fraction from 0 to 1.");
+                }
+            }
+            "--bodies" => {
+                options.bodies = parse_value(&mut args, "--bodies");
+            }
+            "--steps" => {
+                options.steps = parse_value(&mut args, "--steps");
+            }
+            "--closure-n" => {
+                options.closure_n = parse_value(&mut args, "--closure-n");
+            }
+            "--closure" => {
+                let value = args.next().expect("Missing value for --closure.");
+                options.closure = match value.as_str() {
+                    "boxed" => ClosureMode::Boxed,
+                    "static" => ClosureMode::Static,
+                    _ => panic!("Invalid value for --closure. Use 'boxed' or 'static'."),
+                };
+            }
+            "--flood-n" => {
+                options.flood_n = parse_value(&mut args, "--flood-n");
+            }
+            "--iter-n" => {
+                options.iter_n = parse_value(&mut args, "--iter-n");
+            }
+            "--lcs-n" => {
+                options.lcs_n = parse_value(&mut args, "--lcs-n");
+            }
+            "--tail-n" => {
+                options.tail_n = parse_value(&mut args, "--tail-n");
+            }
+            "--gap-n" => {
+                options.gap_n = parse_value(&mut args, "--gap-n");
+            }
+            "--tok-n" => {
+                options.tok_n = parse_value(&mut args, "--tok-n");
+            }
+            "--bignum-digits" => {
+                options.bignum_digits = parse_value(&mut args, "--bignum-digits");
+            }
+            "--bignum-reps" => {
+                options.bignum_reps = parse_value(&mut args, "--bignum-reps");
+            }
+            "--set-n" => {
+                options.set_n = parse_value(&mut args, "--set-n");
+            }
+            "--regex-n" => {
+                options.regex_n = parse_value(&mut args, "--regex-n");
+            }
+            "--vtable-n" => {
+                options.vtable_n = parse_value(&mut args, "--vtable-n");
+            }
+            "--dij-nodes" => {
+                options.dij_nodes = parse_value(&mut args, "--dij-nodes");
+            }
+            "--dij-degree" => {
+                options.dij_degree = parse_value(&mut args, "--dij-degree");
+            }
+            "--horner-degree" => {
+                options.horner_degree = parse_value(&mut args, "--horner-degree");
+            }
+            "--horner-reps" => {
+                options.horner_reps = parse_value(&mut args, "--horner-reps");
+            }
+            "--rc-n" => {
+                options.rc_n = parse_value(&mut args, "--rc-n");
+            }
+            "--rc" => {
+                let value = args.next().expect("Missing value for --rc.");
+                options.rc = match value.as_str() {
+                    "rc" => RcMode::Rc,
+                    "arc" => RcMode::Arc,
+                    _ => panic!("Invalid value for --rc. Use 'rc' or 'arc'."),
+                };
+            }
+            "--matvec-n" => {
+                options.matvec_n = parse_value(&mut args, "--matvec-n");
+            }
+            "--matvec-reps" => {
+                options.matvec_reps = parse_value(&mut args, "--matvec-reps");
+            }
+            "--topo-nodes" => {
+                options.topo_nodes = parse_value(&mut args, "--topo-nodes");
+            }
+            "--topo-edges" => {
+                options.topo_edges = parse_value(&mut args, "--topo-edges");
+            }
+            "--edfs-n" => {
+                options.edfs_n = parse_value(&mut args, "--edfs-n");
+            }
+            "--merkle-leaves" => {
+                options.merkle_leaves = parse_value(&mut args, "--merkle-leaves");
+            }
+            "--life-n" => {
+                options.life_n = parse_value(&mut args, "--life-n");
+            }
+            "--life-steps" => {
+                options.life_steps = parse_value(&mut args, "--life-steps");
+            }
+            "--array-n" => {
+                options.array_n = parse_value(&mut args, "--array-n");
+            }
+            "--bounds" => {
+                let value = args.next().expect("Missing value for --bounds.");
+                options.bounds = match value.as_str() {
+                    "checked" => BoundsMode::Checked,
+                    "unchecked" => BoundsMode::Unchecked,
+                    _ => panic!("Invalid value for --bounds. Use 'checked' or 'unchecked'."),
+                };
+            }
+            "--hist-n" => {
+                options.hist_n = parse_value(&mut args, "--hist-n");
+            }
+            "--hist-buckets" => {
+                options.hist_buckets = parse_value(&mut args, "--hist-buckets");
+            }
+            "--strbuild-n" => {
+                options.strbuild_n = parse_value(&mut args, "--strbuild-n");
+            }
+            "--match-n" => {
+                options.match_n = parse_value(&mut args, "--match-n");
+            }
+            "--bsearch-n" => {
+                options.bsearch_n = parse_value(&mut args, "--bsearch-n");
+            }
+            "--bsearch-queries" => {
+                options.bsearch_queries = parse_value(&mut args, "--bsearch-queries");
+            }
+            "--clone-depth" => {
+                options.clone_depth = parse_value(&mut args, "--clone-depth");
+            }
+            "--clone-width" => {
+                options.clone_width = parse_value(&mut args, "--clone-width");
+            }
+            "--clone-reps" => {
+                options.clone_reps = parse_value(&mut args, "--clone-reps");
+            }
+            "--edit-n" => {
+                options.edit_n = parse_value(&mut args, "--edit-n");
+            }
+            "--expr-n" => {
+                options.expr_n = parse_value(&mut args, "--expr-n");
+            }
+            "--gc-objects" => {
+                options.gc_objects = parse_value(&mut args, "--gc-objects");
+            }
+            "--gc-refs" => {
+                options.gc_refs = parse_value(&mut args, "--gc-refs");
+            }
+            "--seed" => {
+                options.seed = parse_value(&mut args, "--seed");
+            }
+            "--seed-sweep" => {
+                options.seed_sweep = parse_value(&mut args, "--seed-sweep");
+            }
+            "--repeat" => {
+                options.repeat = parse_value(&mut args, "--repeat");
+                repeat_given = true;
+            }
+            "--size-range" => {
+                let low = parse_value(&mut args, "--size-range");
+                let high = parse_value(&mut args, "--size-range");
+                options.size_range = Some((low, high));
+            }
+            "--size-kernel" => {
+                options.size_kernel = Some(parse_value(&mut args, "--size-kernel"));
+            }
+            "--repeat-until-stable" => {
+                options.repeat_until_stable = true;
+            }
+            "--stable-threshold" => {
+                options.stable_threshold = parse_value(&mut args, "--stable-threshold");
+            }
+            "--max-batches" => {
+                options.max_batches = parse_value(&mut args, "--max-batches");
+            }
+            "--shuffle-order" => {
+                options.shuffle_order = true;
+            }
+            "--shuffle-seed" => {
+                options.shuffle_seed = parse_value(&mut args, "--shuffle-seed");
+                shuffle_seed_given = true;
+            }
+            "--per-iteration" => {
+                options.per_iteration = true;
+            }
+            "--perf-counters" => {
+                options.perf_counters = true;
+            }
+            "--parameters-hash" => {
+                options.parameters_hash = true;
+            }
+            "--self-audit" => {
+                options.self_audit = true;
+            }
+            "--self-audit-tolerance" => {
+                options.self_audit_tolerance = parse_value(&mut args, "--self-audit-tolerance");
+            }
+            "--drift-report" => {
+                options.drift_report = true;
+            }
+            "--ramp-report" => {
+                options.ramp_report = true;
+            }
+            "--profile-hot" => {
+                options.profile_hot = true;
+            }
+            "--drift-threshold" => {
+                options.drift_threshold = parse_value(&mut args, "--drift-threshold");
+            }
+            "--strict" => {
+                options.strict = true;
+            }
+            "--samples-out" => {
+                options.samples_out = Some(parse_value(&mut args, "--samples-out"));
+            }
+            "--format" => {
+                format_given = true;
+                let value = args.next().expect("Missing value for --format.");
+                options.format = match value.as_str() {
+                    "csv" => OutputFormat::Csv,
+                    "tsv" => OutputFormat::Tsv,
+                    "table" => OutputFormat::Table,
+                    "influx" => OutputFormat::Influx,
+                    "json" => OutputFormat::Json,
+                    "ndjson" => OutputFormat::Ndjson,
+                    "gnuplot" => OutputFormat::Gnuplot,
+                    "markdown" => OutputFormat::Markdown,
+                    _ => panic!(
+                        "Invalid value for --format. Use 'csv', 'tsv', 'table', 'influx', 'json', 'ndjson', \
+                         'gnuplot', or 'markdown'."
+                    ),
+                };
+            }
+            "--human-numbers" => {
+                options.human_numbers = true;
+            }
+            "--no-header" => {
+                options.no_header = true;
+            }
+            "--csv-quote-always" => {
+                options.csv_quote_always = true;
+            }
+            "--columns" => {
+                options.columns = Some(parse_value(&mut args, "--columns"));
+            }
+            "--summary-line" => {
+                options.summary_line = true;
+            }
+            "--annotate" => {
+                options.annotate = true;
+            }
+            "--output" => {
+                options.output = Some(parse_value(&mut args, "--output"));
+            }
+            "--tee" => {
+                options.tee = Some(parse_value(&mut args, "--tee"));
+            }
+            "--output-dir" => {
+                options.output_dir = Some(parse_value(&mut args, "--output-dir"));
+            }
+            "--emit-all-formats" => {
+                options.output_dir = Some(parse_value(&mut args, "--emit-all-formats"));
+            }
+            "--append" => {
+                options.append = true;
+            }
+            "--resume" => {
+                options.resume = Some(parse_value(&mut args, "--resume"));
+            }
+            "--against-last" => {
+                options.against_last = Some(parse_value(&mut args, "--against-last"));
+            }
+            "--baseline" => {
+                options.baseline = Some(parse_value(&mut args, "--baseline"));
+            }
+            "--geomean" => {
+                options.geomean = true;
+            }
+            "--trend" => {
+                options.trend = Some(parse_value(&mut args, "--trend"));
+            }
+            "--trend-threshold" => {
+                options.trend_threshold = parse_value(&mut args, "--trend-threshold");
+            }
+            "--since" => {
+                let value = args.next().expect("Missing value for --since.");
+                options.since = Some(unix_from_iso8601(&value).unwrap_or_else(|| {
+                    panic!("Invalid value for --since. Use a UTC date such as 2026-01-31 or 2026-01-31T12:00:00Z.")
+                }));
+            }
+            "--verify" => {
+                options.verify = Some(parse_value(&mut args, "--verify"));
+            }
+            "--verify-tolerance" => {
+                options.verify_tolerance = Some(parse_value(&mut args, "--verify-tolerance"));
+            }
+            "--fail-fast" => {
+                options.fail_fast = true;
+            }
+            "--write-reference" => {
+                options.write_reference = Some(parse_value(&mut args, "--write-reference"));
+            }
+            "--self-verify" => {
+                options.self_verify = true;
+            }
+            "--fairness-check" => {
+                options.fairness_check = true;
+            }
+            "--compare-cmd" => {
+                options.compare_cmd = Some(parse_value(&mut args, "--compare-cmd"));
+            }
+            "--yes" => {
+                options.yes = true;
+            }
+            "--log-level" => {
+                let value = args.next().expect("Missing value for --log-level.");
+                options.log_level = match value.as_str() {
+                    "error" => LogLevel::Error,
+                    "warn" => LogLevel::Warn,
+                    "info" => LogLevel::Info,
+                    "debug" => LogLevel::Debug,
+                    _ => panic!("Invalid value for --log-level. Use 'error', 'warn', 'info', or 'debug'."),
+                };
+            }
+            "--verbose" | "-v" => {
+                options.log_level = LogLevel::Info;
+            }
+            "--quiet" | "-q" => {
+                options.log_level = LogLevel::Error;
+            }
+            "--print-config" => {
+                options.print_config = true;
+            }
+            "--emit-env" => {
+                options.emit_env = true;
+            }
+            "--numa-node" => {
+                options.numa_node = Some(parse_value(&mut args, "--numa-node"));
+            }
+            "--init-delay-ms" => {
+                options.init_delay_ms = parse_value(&mut args, "--init-delay-ms");
+            }
+            "--cooldown-ms" => {
+                options.cooldown_ms = parse_value(&mut args, "--cooldown-ms");
+            }
+            "--manifest" => {
+                options.manifest = true;
+            }
+            "--completions" => {
+                let value = args.next().expect("Missing value for --completions.");
+                options.completions = match value.as_str() {
+                    "bash" => Some(Shell::Bash),
+                    "zsh" => Some(Shell::Zsh),
+                    "fish" => Some(Shell::Fish),
+                    _ => panic!("Invalid value for --completions. Use 'bash', 'zsh', or 'fish'."),
+                };
+            }
+            _ => {
+                unreachable!("'{}' is listed in CLI_OPTIONS but not handled.", arg);
+            }
+        }
+    }
+
+    LOG_LEVEL.store(options.log_level as usize, Ordering::Relaxed);
+    INPUT_RNG.store(options.rng as usize, Ordering::Relaxed);
+    STRICT.store(options.strict, Ordering::Relaxed);
+
+    if options.iterations == 0 {
+        panic!("--iterations must be greater than zero.");
+    }
+
+    if options.threads == 0 {
+        panic!("--threads must be greater than zero.");
+    }
+
+    // The plan lays out --max-batches batches; the main loop skips the rest
+    // of an algorithm's batches once its running mean has settled.
+    if options.repeat_until_stable {
+        if repeat_given {
+            panic!("--repeat-until-stable chooses the batch count; cap it with --max-batches instead of --repeat.");
+        }
+        if options.max_batches < 2 {
+            panic!("--max-batches must be at least 2.");
+        }
+        options.repeat = options.max_batches;
+    }
+
+    if options.repeat == 0 || options.seed_sweep == 0 {
+        panic!("--repeat and --seed-sweep must be greater than zero.");
+    }
+
+    match (options.size_range, &options.size_kernel) {
+        (Some(_), None) => panic!("--size-range needs --size-kernel to name the kernel it resizes."),
+        (None, Some(_)) => panic!("--size-kernel only applies together with --size-range."),
+        (Some((low, high)), Some(name)) => {
+            if low > high {
+                panic!("Invalid value for --size-range. The low end must not exceed the high end.");
+            }
+            if !KERNELS.iter().any(|kernel| kernel.name == name) {
+                panic!("Unknown kernel '{}' for --size-kernel.", name);
+            }
+        }
+        (None, None) => {}
+    }
+
+    if options.ramp_report && !iterations_given {
+        options.iterations = RAMP_ITERATIONS;
+    }
+
+    if options.shuffle_order && !shuffle_seed_given {
+        warn!(
+            "no --shuffle-seed given; shuffling with --shuffle-seed {} (pass it to reproduce this order).",
+            options.shuffle_seed
+        );
+    }
+
+    if !format_given {
+        if let Some(path) = &options.output {
+            options.format = format_for_path(path);
+        }
+    }
+
+    if let Some(path) = options.resume.clone() {
+        if options.format != OutputFormat::Csv {
+            panic!("--resume requires the csv format.");
+        }
+        if options.output.as_ref().is_some_and(|output| *output != path) {
+            panic!("--resume appends to its own file; do not combine it with a different --output.");
+        }
+        options.output = Some(path);
+        options.append = true;
+    }
+    if let Some(list) = &options.columns {
+        let available = available_columns(&options);
+        for name in list.split(',') {
+            if !available.iter().any(|column| column.name() == name) {
+                let names: Vec<&str> = available.iter().map(|column| column.name()).collect();
+                panic!(
+                    "Invalid value for --columns: unknown column '{}'. Valid columns: {}.",
+                    name,
+                    names.join(", ")
+                );
+            }
+        }
+    }
+    if options.fail_fast && options.verify.is_none() {
+        panic!("--fail-fast only applies to --verify.");
+    }
+    if options.tee.is_some() && options.output.is_some() {
+        panic!("--tee already writes results to stdout and a file; do not combine it with --output or --resume.");
+    }
+
+    options
+}
+
+fn run_sum_xor(n: u64) -> u64 {
+    let mut acc = 0u64;
+    for i in 1..=n {
+        acc = acc.wrapping_add((i ^ (i >> 3)).wrapping_add(i % 8));
+    }
+    acc
+}
+
+fn run_prime_trial(n: usize) -> u64 {
+    if n < 2 {
+        return 0;
+    }
+
+    let mut prime_count = 0u64;
+    let mut checksum = 0u64;
+    for candidate in 2..=n {
+        let mut divisor = 2usize;
+        let mut is_prime = true;
+        while divisor * divisor <= candidate {
+            if candidate % divisor == 0 {
+                is_prime = false;
+                break;
+            }
+            divisor += 1;
+        }
+
+        if !is_prime {
+            continue;
+        }
+
+        prime_count += 1;
+        checksum = checksum.wrapping_add((candidate as u64).wrapping_mul((prime_count % 16) + 1));
+    }
+
+    (prime_count << 32) ^ checksum
+}
+
+fn run_affine_grid(n: usize) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+
+    let mut checksum = 0u64;
+    for row in 0..n {
+        for col in 0..n {
+            let mut acc = 0u64;
+            for k in 0..n {
+                let a = (((row * 131) + (k * 17) + 13) % 256) as u64;
+                let b = (((k * 19) + (col * 97) + 53) % 256) as u64;
+                acc = acc.wrapping_add(a.wrapping_mul(b));
+            }
+
+            let index = (row as u64)
+                .wrapping_mul(n as u64)
+                .wrapping_add(col as u64);
+            checksum ^= acc.wrapping_add(index.wrapping_mul(2_654_435_761u64));
+        }
+    }
+
+    checksum
+}
+
+fn run_branch_mix(n: u64) -> u64 {
+    let mut acc = 0u64;
+    for i in 1..=n {
+        if (i % 2) == 0 {
+            acc = acc.wrapping_add(i << 1);
+        } else {
+            acc ^= i.wrapping_mul(3);
+        }
+
+        if (i % 7) == 0 {
+            acc = acc.wrapping_add(i >> 2);
+        } else {
+            acc ^= i % 16;
+        }
+
+        if (i % 97) == 0 {
+            acc = acc.wrapping_add(i.wrapping_mul((i % 13) + 1));
+        }
+    }
+
+    acc
+}
+
+fn run_gcd_fold(n: usize) -> u64 {
+    let mut checksum = 0u64;
+    for i in 1..=n as u64 {
+        let mut a = i.wrapping_mul(37).wrapping_add(17);
+        let mut b = i.wrapping_mul(53).wrapping_add(19);
+        while b != 0 {
+            let t = a % b;
+            a = b;
+            b = t;
+        }
+
+        checksum = checksum.wrapping_add(a.wrapping_mul((i % 16) + 1));
+    }
+
+    checksum
+}
+
+fn run_lcg_stream(n: u64, seed: u64) -> u64 {
+    let mut state = seed;
+    let mut checksum = 0u64;
+    for _ in 0..n {
+        state = (state.wrapping_mul(1_103_515_245).wrapping_add(12_345)) % 2_147_483_647;
+        if (state % 2) == 0 {
+            checksum = checksum.wrapping_add(state);
+        } else {
+            checksum ^= state;
+        }
+    }
+
+    checksum ^ state
+}
+
+const DEFAULT_SEED: u64 = 123_456_789;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum RngKind {
+    Lcg,
+    Xorshift,
+    Splitmix64,
+}
+
+impl RngKind {
+    fn name(self) -> &'static str {
+        match self {
+            RngKind::Lcg => "lcg",
+            RngKind::Xorshift => "xorshift",
+            RngKind::Splitmix64 => "splitmix64",
+        }
+    }
+}
+
+// The splitmix64 output finalizer, also used on its own as a 64-bit mixer.
+fn splitmix64_mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// Selected once by --rng, like the log level, so kernels only need a seed.
+static INPUT_RNG: AtomicUsize = AtomicUsize::new(RngKind::Lcg as usize);
+
+// Reproducible pseudo-random input data for the data-driven kernels. The
+// default is the same recurrence as `lcg_stream`. Every generator yields
+// values in [0, 2^31), so kernels see the same range whichever is selected:
+// - lcg: `state = (state * 1103515245 + 12345) % 2147483647`, output `state`
+// - xorshift: `x ^= x << 13; x ^= x >> 7; x ^= x << 17` on 64 bits (a zero
+//   seed is replaced by 0x9e3779b97f4a7c15), output `x >> 33`
+// - splitmix64: `state += 0x9e3779b97f4a7c15`, then the splitmix64 finalizer
+//   on `state`, output `z >> 33`
+struct InputRng {
+    kind: RngKind,
+    state: u64,
+}
+
+impl InputRng {
+    fn new(seed: u64) -> InputRng {
+        let kind = match INPUT_RNG.load(Ordering::Relaxed) {
+            1 => RngKind::Xorshift,
+            2 => RngKind::Splitmix64,
+            _ => RngKind::Lcg,
+        };
+        InputRng::with_kind(kind, seed)
+    }
+
+    fn with_kind(kind: RngKind, seed: u64) -> InputRng {
+        let state = if kind == RngKind::Xorshift && seed == 0 {
+            0x9e37_79b9_7f4a_7c15
+        } else {
+            seed
+        };
+        InputRng { kind, state }
+    }
+
+    fn next(&mut self) -> u64 {
+        match self.kind {
+            RngKind::Lcg => {
+                self.state = (self.state.wrapping_mul(1_103_515_245).wrapping_add(12_345)) % 2_147_483_647;
+                self.state
+            }
+            RngKind::Xorshift => {
+                self.state ^= self.state << 13;
+                self.state ^= self.state >> 7;
+                self.state ^= self.state << 17;
+                self.state >> 33
+            }
+            RngKind::Splitmix64 => {
+                self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                splitmix64_mix(self.state) >> 33
+            }
+        }
+    }
+}
+
+// Sorts `(key, original_index)` pairs with a bottom-up merge sort that takes
+// from the left run on equal keys, so equal keys keep their original order.
+// Keys are `lcg % 1024`, giving plenty of ties; the fold is order-sensitive,
+// so an unstable sort produces a different checksum.
+fn run_sort_pairs(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut pairs: Vec<(u64, u64)> = (0..n as u64).map(|index| (rng.next() % 1024, index)).collect();
+    let mut buffer = pairs.clone();
+
+    let mut width = 1;
+    while width < n {
+        let mut start = 0;
+        while start < n {
+            let mid = (start + width).min(n);
+            let end = (start + 2 * width).min(n);
+            let (mut left, mut right) = (start, mid);
+            for slot in buffer[start..end].iter_mut() {
+                if right >= end || (left < mid && pairs[left].0 <= pairs[right].0) {
+                    *slot = pairs[left];
+                    left += 1;
+                } else {
+                    *slot = pairs[right];
+                    right += 1;
+                }
+            }
+            start = end;
+        }
+        std::mem::swap(&mut pairs, &mut buffer);
+        width *= 2;
+    }
+
+    let mut checksum = 0u64;
+    for (key, index) in pairs {
+        checksum = checksum
+            .wrapping_mul(1_099_511_628_211)
+            .wrapping_add((key << 32) | index);
+    }
+    checksum
+}
+
+// A dependency chain of 64-bit divisions: each quotient becomes the next
+// dividend (with the top bit forced so it stays large), and the divisor is
+// `((checksum ^ i) % 251) + 2`, so it varies every step and is never zero.
+fn run_div_stream(n: u64) -> u64 {
+    let mut dividend = u64::MAX;
+    let mut checksum = 0u64;
+    for i in 1..=n {
+        let divisor = ((checksum ^ i) % 251) + 2;
+        let quotient = dividend / divisor;
+        checksum = checksum.wrapping_add(quotient ^ i);
+        dividend = quotient | (1 << 63);
+    }
+
+    checksum
+}
+
+// Maps an input generator output onto [-1.0, 1.0] in steps of 1e-6.
+fn rng_unit(rng: &mut InputRng) -> f64 {
+    (rng.next() % 2_000_001) as f64 / 1_000_000.0 - 1.0
+}
+
+// Sums slices of up to 8 values left to right, otherwise splits at len / 2
+// and adds the two halves.
+fn pairwise_sum(values: &[f64]) -> f64 {
+    if values.len() <= 8 {
+        return values.iter().fold(0.0, |acc, value| acc + value);
+    }
+
+    let (left, right) = values.split_at(values.len() / 2);
+    pairwise_sum(left) + pairwise_sum(right)
+}
+
+// Dot product of two vectors drawn from one LCG stream (all of `a`, then all
+// of `b`). The serial and pairwise orders round differently, so they produce
+// different checksums by design; compare like with like.
+fn run_dot_product(n: usize, order: DotOrder, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let a: Vec<f64> = (0..n).map(|_| rng_unit(&mut rng)).collect();
+    let b: Vec<f64> = (0..n).map(|_| rng_unit(&mut rng)).collect();
+
+    let sum = match order {
+        DotOrder::Serial => a.iter().zip(b.iter()).fold(0.0, |acc, (x, y)| acc + x * y),
+        DotOrder::Pairwise => {
+            let products: Vec<f64> = a.iter().zip(b.iter()).map(|(x, y)| x * y).collect();
+            pairwise_sum(&products)
+        }
+    };
+
+    // Quantize to 1e-12: coarse enough to be an exact integer, fine enough to
+    // keep the rounding differences between the two orders visible.
+    (sum * 1e12).round() as i64 as u64
+}
+
+// `threads` workers each increment one shared counter `n` times. The result
+// is always `n * threads`; the timing is what matters, and it depends far more
+// on core count and cache topology than on single-thread speed.
+fn run_atomic_contend(n: u64, threads: usize) -> u64 {
+    let counter = AtomicU64::new(0);
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for _ in 0..n {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+
+    counter.load(Ordering::Relaxed)
+}
+
+// 64-bit FNV-1a over the UTF-8 bytes.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+// Keys are `format!("k{:x}", lcg % (2 * n))`. The first `n` draws are
+// inserted (repeats bump a count), the next `n` are looked up, so roughly
+// half of the lookups hit. The table is open addressing with linear probing
+// over a power-of-two capacity of at least `2 * n`, indexed by FNV-1a.
+fn run_string_map(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let range = (2 * n as u64).max(1);
+    let capacity = (2 * n).max(1).next_power_of_two();
+    let mask = capacity - 1;
+    let mut keys: Vec<Option<String>> = vec![None; capacity];
+    let mut counts = vec![0u64; capacity];
+
+    for _ in 0..n {
+        let key = format!("k{:x}", rng.next() % range);
+        let mut slot = fnv1a(key.as_bytes()) as usize & mask;
+        loop {
+            match &keys[slot] {
+                Some(existing) if *existing == key => break,
+                Some(_) => slot = (slot + 1) & mask,
+                None => {
+                    keys[slot] = Some(key);
+                    break;
+                }
+            }
+        }
+        counts[slot] += 1;
+    }
+
+    let mut hits = 0u64;
+    let mut checksum = 0u64;
+    for _ in 0..n {
+        let key = format!("k{:x}", rng.next() % range);
+        let mut slot = fnv1a(key.as_bytes()) as usize & mask;
+        let mut found = 0u64;
+        while let Some(existing) = &keys[slot] {
+            if *existing == key {
+                found = counts[slot];
+                break;
+            }
+            slot = (slot + 1) & mask;
+        }
+
+        if found > 0 {
+            hits += 1;
+        }
+        checksum = checksum.wrapping_mul(0x0000_0100_0000_01b3) ^ found;
+    }
+
+    (hits << 32) ^ checksum
+}
+
+// Builds `n` bytes as runs: each run takes `lcg % 256` as its byte and
+// `lcg % 12 + 1` as its length (truncated at the end of the buffer). The
+// buffer is encoded as `(count, byte)` pairs with counts capped at 255,
+// decoded again, and checked against the input.
+fn run_rle_roundtrip(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut input = Vec::with_capacity(n);
+    while input.len() < n {
+        let byte = (rng.next() % 256) as u8;
+        let run = (rng.next() % 12 + 1) as usize;
+        let run = run.min(n - input.len());
+        input.extend(std::iter::repeat_n(byte, run));
+    }
+
+    let mut encoded = Vec::new();
+    let mut index = 0;
+    while index < input.len() {
+        let byte = input[index];
+        let mut count = 1;
+        while count < 255 && index + count < input.len() && input[index + count] == byte {
+            count += 1;
+        }
+        encoded.push(count as u8);
+        encoded.push(byte);
+        index += count;
+    }
+
+    let mut decoded = Vec::with_capacity(n);
+    for pair in encoded.chunks_exact(2) {
+        decoded.extend(std::iter::repeat_n(pair[1], pair[0] as usize));
+    }
+
+    assert!(decoded == input, "rle_roundtrip: decoded buffer differs from input.");
+    ((encoded.len() as u64) << 32) ^ fnv1a(&decoded)
+}
+
+// Parses an optionally `-`-prefixed run of ASCII digits.
+fn parse_decimal(token: &[u8]) -> i64 {
+    let (negative, digits) = match token.split_first() {
+        Some((b'-', rest)) => (true, rest),
+        _ => (false, token),
+    };
+
+    let mut value = 0i64;
+    for digit in digits {
+        value = value * 10 + (digit - b'0') as i64;
+    }
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+// Writes `n` integers `lcg - 1073741823` (so about half are negative) as
+// plain decimal text separated by single spaces, then parses them back with
+// a hand-written parser. The checksum folds the wrapping sum of the parsed
+// values with the token count.
+fn run_parse_ints(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut text = String::with_capacity(n * 11);
+    for index in 0..n {
+        if index > 0 {
+            text.push(' ');
+        }
+        text.push_str(&(rng.next() as i64 - 1_073_741_823).to_string());
+    }
+
+    let mut count = 0u64;
+    let mut sum = 0i64;
+    for token in text.as_bytes().split(|byte| *byte == b' ') {
+        if token.is_empty() {
+            continue;
+        }
+        sum = sum.wrapping_add(parse_decimal(token));
+        count += 1;
+    }
+
+    (sum as u64).wrapping_mul(31).wrapping_add(count)
+}
+
+// Pushes `n` LCG values into a hand-written array-backed binary max-heap,
+// then pops them all. A correct heap pops in non-increasing order, so the
+// order-sensitive fold also checks a port's heap implementation.
+fn run_heap_ops(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut heap: Vec<u64> = Vec::with_capacity(n);
+    for _ in 0..n {
+        heap.push(rng.next());
+        let mut child = heap.len() - 1;
+        while child > 0 {
+            let parent = (child - 1) / 2;
+            if heap[parent] >= heap[child] {
+                break;
+            }
+            heap.swap(parent, child);
+            child = parent;
+        }
+    }
+
+    let mut checksum = 0u64;
+    while let Some(last) = heap.pop() {
+        let top = if heap.is_empty() {
+            last
+        } else {
+            let top = heap[0];
+            heap[0] = last;
+            let mut parent = 0;
+            loop {
+                let left = 2 * parent + 1;
+                let right = left + 1;
+                let mut largest = parent;
+                if left < heap.len() && heap[left] > heap[largest] {
+                    largest = left;
+                }
+                if right < heap.len() && heap[right] > heap[largest] {
+                    largest = right;
+                }
+                if largest == parent {
+                    break;
+                }
+                heap.swap(parent, largest);
+                parent = largest;
+            }
+            top
+        };
+        checksum = checksum.wrapping_mul(1_099_511_628_211).wrapping_add(top);
+    }
+
+    checksum
+}
+
+// Copies a `size_kb` KiB buffer `reps` times. Byte `i` starts as
+// `(i * 131 + 7) % 256`. Each rep does a full non-overlapping copy into the
+// destination (memcpy), then moves the destination's first half onto itself
+// at offset `len / 4` (overlapping, memmove), samples byte
+// `(rep * 4099) % len`, and writes `sample ^ rep` back into the source at
+// `rep % len` so no copy can be skipped. Ports may use their native copy
+// primitives; only the sampled bytes feed the checksum.
+fn run_memcopy(size_kb: usize, reps: usize) -> u64 {
+    let len = (size_kb * 1024).max(4);
+    let mut src: Vec<u8> = (0..len).map(|i| ((i * 131 + 7) % 256) as u8).collect();
+    let mut dst = vec![0u8; len];
+    let mut checksum = 0u64;
+    for rep in 0..reps {
+        dst.copy_from_slice(&src);
+        dst.copy_within(0..len / 2, len / 4);
+        let sample = dst[(rep * 4099) % len];
+        checksum = checksum.wrapping_mul(31).wrapping_add(sample as u64 + rep as u64);
+        src[rep % len] = sample ^ rep as u8;
+    }
+
+    checksum
+}
+
+// Integer square roots of `n` inputs `lcg + 1` via floating-point Newton
+// steps `x = 0.5 * (x + v / x)` starting from `x = v + 1`. The iteration stops
+// at the first step that does not decrease `x`; `floor(x)` is then corrected
+// by integer checks so the root is exact. Both the roots and the per-input
+// step counts feed the checksum, so ports must follow the same criterion.
+fn run_newton_sqrt(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut checksum = 0u64;
+    for _ in 0..n {
+        let value = rng.next() + 1;
+        let target = value as f64;
+        let mut x = target + 1.0;
+        let mut steps = 0u64;
+        loop {
+            let next = 0.5 * (x + target / x);
+            steps += 1;
+            if next >= x {
+                break;
+            }
+            x = next;
+        }
+
+        let mut root = x as u64;
+        while root * root > value {
+            root -= 1;
+        }
+        while (root + 1) * (root + 1) <= value {
+            root += 1;
+        }
+
+        checksum = checksum
+            .wrapping_mul(1_099_511_628_211)
+            .wrapping_add((root << 8) | steps);
+    }
+
+    checksum
+}
+
+// Sets, clears, and toggles bits of an `n`-bit array at LCG indices
+// `lcg % n`, cycling set/clear/toggle over the `n` operations, then counts
+// the set bits. Bits are packed LSB-first into 64-bit words (bit `i` is bit
+// `i % 64` of word `i / 64`); the XOR of all words is folded in with the
+// count, so a port with a different ordering fails the checksum.
+fn run_bitset_ops(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut words = vec![0u64; n.div_ceil(64)];
+    for op in 0..n {
+        let bit = rng.next() as usize % n;
+        let mask = 1u64 << (bit % 64);
+        let word = &mut words[bit / 64];
+        match op % 3 {
+            0 => *word |= mask,
+            1 => *word &= !mask,
+            _ => *word ^= mask,
+        }
+    }
+
+    let mut count = 0u64;
+    let mut folded = 0u64;
+    for word in &words {
+        count += u64::from(word.count_ones());
+        folded ^= *word;
+    }
+
+    count.wrapping_mul(1_099_511_628_211) ^ folded
+}
+
+const VM_PROGRAM_LEN: usize = 256;
+
+struct VmState {
+    acc: u64,
+    memory: [u64; 16],
+}
+
+struct VmInstruction {
+    opcode: usize,
+    operand: u64,
+}
+
+// Handlers return how many instructions to advance, so a taken branch can
+// skip the next one.
+type VmHandler = fn(&mut VmState, u64) -> usize;
+
+const VM_HANDLERS: [VmHandler; 6] = [
+    |vm, operand| {
+        vm.acc = vm.acc.wrapping_add(operand);
+        1
+    },
+    |vm, operand| {
+        vm.acc = vm.acc.wrapping_mul(operand | 1);
+        1
+    },
+    |vm, operand| {
+        vm.acc = (vm.acc ^ operand).rotate_left(5);
+        1
+    },
+    |vm, operand| {
+        vm.acc = vm.acc.wrapping_add(vm.memory[operand as usize % 16]);
+        1
+    },
+    |vm, operand| {
+        vm.memory[operand as usize % 16] = vm.acc;
+        1
+    },
+    |vm, _| if vm.acc % 2 == 1 { 2 } else { 1 },
+];
+
+// Interprets a generated 256-instruction program through a function-pointer
+// table for `n` steps, wrapping around at the end of the program. Each
+// instruction draws three LCG values in order: `class % 100 < arith_pct`
+// picks arithmetic (add, mul by `operand | 1`, xor-rotate) over
+// branch/memory (load, store, skip-next-if-odd), `kind % 3` picks the
+// opcode within the class, and `operand % 256` is the immediate. Memory has
+// 16 slots indexed by `operand % 16`; the accumulator starts at the seed.
+fn run_vm_mix(n: usize, arith_pct: u64, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let program: Vec<VmInstruction> = (0..VM_PROGRAM_LEN)
+        .map(|_| {
+            let class = if rng.next() % 100 < arith_pct { 0 } else { 3 };
+            let kind = rng.next() as usize % 3;
+            VmInstruction {
+                opcode: class + kind,
+                operand: rng.next() % 256,
+            }
+        })
+        .collect();
+
+    let mut vm = VmState {
+        acc: seed,
+        memory: [0; 16],
+    };
+    let mut pc = 0;
+    for _ in 0..n {
+        let instruction = &program[pc];
+        pc = (pc + VM_HANDLERS[instruction.opcode](&mut vm, instruction.operand)) % VM_PROGRAM_LEN;
+    }
+
+    vm.memory
+        .iter()
+        .fold(vm.acc, |checksum, slot| checksum.wrapping_mul(1_099_511_628_211) ^ slot)
+}
+
+// Breadth-first search from node 0 over an undirected pseudo-random graph.
+// For each node `u` in order, `degree` LCG draws give neighbours
+// `v = lcg % nodes`; each edge is appended to the adjacency lists of both
+// `u` and `v` (self-loops included), and BFS visits neighbours in list
+// order with a FIFO queue. Every visited node and its distance are folded
+// in visit order, so ports must build the lists in the same order.
+fn run_bfs(nodes: usize, degree: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut adjacency: Vec<Vec<u32>> = vec![Vec::new(); nodes];
+    for u in 0..nodes {
+        for _ in 0..degree {
+            let v = rng.next() as usize % nodes;
+            adjacency[u].push(v as u32);
+            adjacency[v].push(u as u32);
+        }
+    }
+
+    let mut distance = vec![u32::MAX; nodes];
+    let mut queue = VecDeque::with_capacity(nodes);
+    let mut checksum = 0u64;
+    if nodes > 0 {
+        distance[0] = 0;
+        queue.push_back(0usize);
+    }
+    while let Some(node) = queue.pop_front() {
+        checksum = checksum
+            .wrapping_mul(1_099_511_628_211)
+            .wrapping_add(((node as u64) << 16) | u64::from(distance[node]));
+        for &next in &adjacency[node] {
+            let next = next as usize;
+            if distance[next] == u32::MAX {
+                distance[next] = distance[node] + 1;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    checksum
+}
+
+struct BstNode {
+    key: u64,
+    left: Option<Box<BstNode>>,
+    right: Option<Box<BstNode>>,
+}
+
+// Inserts `n` LCG keys into an unbalanced binary search tree of boxed nodes
+// (equal keys go right), then folds the keys in in-order sequence. In-order
+// traversal of a correct tree yields the keys sorted, so the checksum also
+// validates a port's insertion logic.
+fn run_bst(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut root: Option<Box<BstNode>> = None;
+    for _ in 0..n {
+        let key = rng.next();
+        let mut slot = &mut root;
+        while let Some(node) = slot {
+            slot = if key < node.key { &mut node.left } else { &mut node.right };
+        }
+        *slot = Some(Box::new(BstNode {
+            key,
+            left: None,
+            right: None,
+        }));
+    }
+
+    let mut checksum = 0u64;
+    let mut stack: Vec<&BstNode> = Vec::new();
+    let mut current = root.as_deref();
+    while current.is_some() || !stack.is_empty() {
+        while let Some(node) = current {
+            stack.push(node);
+            current = node.left.as_deref();
+        }
+        if let Some(node) = stack.pop() {
+            checksum = checksum.wrapping_mul(1_099_511_628_211).wrapping_add(node.key);
+            current = node.right.as_deref();
+        }
+    }
+
+    checksum
+}
+
+// Folds `n` LCG values into a signed 32-bit accumulator with saturating
+// arithmetic. Values with `lcg % 8 == 0` multiply the accumulator by
+// `lcg % 5 - 2`; all others add `lcg - 2^30`, a step in [-2^30, 2^30) that
+// regularly drives it into the i32 bounds. The accumulator is folded after
+// every step. Clamping is the point, so the checksum intentionally differs
+// from anything computed with wrapping arithmetic.
+fn run_saturate_fold(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut acc = 0i32;
+    let mut checksum = 0u64;
+    for _ in 0..n {
+        let value = rng.next();
+        acc = if value.is_multiple_of(8) {
+            acc.saturating_mul((value % 5) as i32 - 2)
+        } else {
+            acc.saturating_add((value as i64 - (1 << 30)) as i32)
+        };
+        checksum = checksum
+            .wrapping_mul(1_099_511_628_211)
+            .wrapping_add(u64::from(acc as u32));
+    }
+
+    checksum
+}
+
+const LAYOUT_STEPS: usize = 8;
+const LAYOUT_DT: f64 = 0.01;
+
+#[derive(Clone, Copy)]
+struct Particle {
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+}
+
+// Advances `n` particles (x, y, vx, vy drawn with `rng_unit` in that order
+// per particle) through 8 steps of `vy -= 9.81 * dt; x += vx * dt;
+// y += vy * dt` with `dt = 0.01`, then sums `x + y` over the particles in
+// index order. Both layouts perform the same floating-point operations in
+// the same order, so the checksum (the bits of the sum) is identical for
+// `soa` and `aos`; only the memory access pattern differs.
+fn run_layout(n: usize, layout: Layout, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let particles: Vec<Particle> = (0..n)
+        .map(|_| Particle {
+            x: rng_unit(&mut rng),
+            y: rng_unit(&mut rng),
+            vx: rng_unit(&mut rng),
+            vy: rng_unit(&mut rng),
+        })
+        .collect();
+
+    let sum = match layout {
+        Layout::Aos => {
+            let mut particles = particles;
+            for _ in 0..LAYOUT_STEPS {
+                for particle in &mut particles {
+                    particle.vy -= 9.81 * LAYOUT_DT;
+                    particle.x += particle.vx * LAYOUT_DT;
+                    particle.y += particle.vy * LAYOUT_DT;
+                }
+            }
+            particles.iter().fold(0.0, |sum, particle| sum + (particle.x + particle.y))
+        }
+        Layout::Soa => {
+            let mut x: Vec<f64> = particles.iter().map(|particle| particle.x).collect();
+            let mut y: Vec<f64> = particles.iter().map(|particle| particle.y).collect();
+            let vx: Vec<f64> = particles.iter().map(|particle| particle.vx).collect();
+            let mut vy: Vec<f64> = particles.iter().map(|particle| particle.vy).collect();
+            for _ in 0..LAYOUT_STEPS {
+                for i in 0..n {
+                    vy[i] -= 9.81 * LAYOUT_DT;
+                    x[i] += vx[i] * LAYOUT_DT;
+                    y[i] += vy[i] * LAYOUT_DT;
+                }
+            }
+            (0..n).fold(0.0, |sum, i| sum + (x[i] + y[i]))
+        }
+    };
+
+    sum.to_bits()
+}
+
+// Inclusive prefix sum over `n` LCG values: `out[i] = v[0] + ... + v[i]`
+// modulo 2^64 (wrapping u64 addition, so a blocked or parallel scan gives
+// the same array). Folds the final element and the element at `n / 2`.
+fn run_prefix_sum(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut values: Vec<u64> = (0..n).map(|_| rng.next()).collect();
+    let mut running = 0u64;
+    for value in &mut values {
+        running = running.wrapping_add(*value);
+        *value = running;
+    }
+
+    match values.last() {
+        Some(last) => last.wrapping_mul(1_099_511_628_211) ^ values[n / 2],
+        None => 0,
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Base64-encodes `n` LCG bytes (`lcg % 256`) with the standard RFC 4648
+// alphabet and `=` padding, decodes them back through a 256-entry reverse
+// table, and checks the round trip. The checksum combines the encoded
+// length with an FNV-1a hash of the encoded text.
+fn run_base64_roundtrip(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let input: Vec<u8> = (0..n).map(|_| (rng.next() % 256) as u8).collect();
+
+    let mut encoded = Vec::with_capacity(n.div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b0 = chunk[0] as usize;
+        let b1 = chunk.get(1).map_or(0, |byte| *byte as usize);
+        let b2 = chunk.get(2).map_or(0, |byte| *byte as usize);
+        encoded.push(BASE64_ALPHABET[b0 >> 2]);
+        encoded.push(BASE64_ALPHABET[((b0 & 0x03) << 4) | (b1 >> 4)]);
+        encoded.push(if chunk.len() > 1 { BASE64_ALPHABET[((b1 & 0x0f) << 2) | (b2 >> 6)] } else { b'=' });
+        encoded.push(if chunk.len() > 2 { BASE64_ALPHABET[b2 & 0x3f] } else { b'=' });
+    }
+
+    let mut reverse = [0u8; 256];
+    for (index, symbol) in BASE64_ALPHABET.iter().enumerate() {
+        reverse[*symbol as usize] = index as u8;
+    }
+    let mut decoded = Vec::with_capacity(n);
+    for quad in encoded.chunks(4) {
+        let sextet = |index: usize| u32::from(reverse[quad[index] as usize]);
+        let bits = (sextet(0) << 18) | (sextet(1) << 12) | (sextet(2) << 6) | sextet(3);
+        decoded.push((bits >> 16) as u8);
+        if quad[2] != b'=' {
+            decoded.push((bits >> 8) as u8);
+        }
+        if quad[3] != b'=' {
+            decoded.push(bits as u8);
+        }
+    }
+
+    assert!(decoded == input, "base64_roundtrip: decoded buffer differs from input.");
+    ((encoded.len() as u64) << 32) ^ fnv1a(&encoded)
+}
+
+// Fails for inputs with `value % 1_000_000 < threshold`, the deterministic
+// error pattern of `error_flow`.
+#[inline(never)]
+fn error_flow_check(value: u64, threshold: u64) -> Result<u64, ()> {
+    if value % 1_000_000 < threshold {
+        Err(())
+    } else {
+        Ok(value)
+    }
+}
+
+#[inline(never)]
+fn error_flow_step(value: u64, threshold: u64) -> Result<u64, ()> {
+    let checked = error_flow_check(value, threshold)?;
+    Ok(checked.wrapping_mul(31) ^ (checked >> 7))
+}
+
+// Draws `n` input values and passes each through two call levels that
+// propagate failures with `?`. An input fails when
+// `value % 1_000_000 < rate * 1_000_000` (rounded down), so the success
+// count and the fold over successful results reproduce across ports.
+fn run_error_flow(n: usize, rate: f64, seed: u64) -> u64 {
+    let threshold = (rate * 1_000_000.0) as u64;
+    let mut rng = InputRng::new(seed);
+    let mut successes = 0u64;
+    let mut checksum = 0u64;
+    for _ in 0..n {
+        if let Ok(value) = error_flow_step(rng.next(), threshold) {
+            successes += 1;
+            checksum = checksum.wrapping_mul(1_099_511_628_211).wrapping_add(value);
+        }
+    }
+
+    checksum ^ (successes << 40)
+}
+
+// Semi-implicit Euler integration of `bodies` particles (x, y, vx, vy drawn
+// with `rng_unit` in that order) in the spring field `a = -k * p` with
+// `k = 4` and `dt = 0.001`. Each step updates every body in index order as
+// `vx += -k * x * dt; vy += -k * y * dt; x += vx * dt; y += vy * dt`. The
+// final state is folded per body as `round(value * 1e9)` for x, y, vx, vy,
+// so ports that keep this operation order match exactly.
+fn run_euler_step(bodies: usize, steps: usize, seed: u64) -> u64 {
+    const K: f64 = 4.0;
+    const DT: f64 = 0.001;
+
+    let mut rng = InputRng::new(seed);
+    let mut state: Vec<[f64; 4]> = (0..bodies)
+        .map(|_| [rng_unit(&mut rng), rng_unit(&mut rng), rng_unit(&mut rng), rng_unit(&mut rng)])
+        .collect();
+    for _ in 0..steps {
+        for body in &mut state {
+            body[2] += -K * body[0] * DT;
+            body[3] += -K * body[1] * DT;
+            body[0] += body[2] * DT;
+            body[1] += body[3] * DT;
+        }
+    }
+
+    state.iter().flatten().fold(0u64, |checksum, value| {
+        checksum
+            .wrapping_mul(1_099_511_628_211)
+            .wrapping_add((value * 1e9).round() as i64 as u64)
+    })
+}
+
+// One step closure of `closure_apply`. All of them share the running
+// accumulator and differ only in the captured constant.
+fn closure_step(acc: &Cell<u64>, constant: u64) -> impl Fn(u64) -> u64 + '_ {
+    move |index| {
+        acc.set((acc.get().rotate_left(7) ^ constant).wrapping_add(index));
+        acc.get()
+    }
+}
+
+// Builds four closures capturing a shared accumulator (starting at the seed)
+// and one input value each, then applies closure `i % 4` to `i` for
+// `i = 0..n`: `acc = (acc.rotl(7) ^ constant) + i` with wrapping addition,
+// folding every returned accumulator. `boxed` calls them through
+// `Box<dyn Fn>`, `static` through one monomorphized closure type; the
+// checksum is the same for both.
+fn run_closure_apply(n: usize, mode: ClosureMode, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let constants: Vec<u64> = (0..4).map(|_| rng.next()).collect();
+    let acc = Cell::new(seed);
+    let mut checksum = 0u64;
+    match mode {
+        ClosureMode::Boxed => {
+            let closures: Vec<Box<dyn Fn(u64) -> u64 + '_>> = constants
+                .iter()
+                .map(|constant| Box::new(closure_step(&acc, *constant)) as Box<dyn Fn(u64) -> u64>)
+                .collect();
+            for index in 0..n {
+                checksum ^= closures[index % 4](index as u64);
+            }
+        }
+        ClosureMode::Static => {
+            let closures: Vec<_> = constants.iter().map(|constant| closure_step(&acc, *constant)).collect();
+            for index in 0..n {
+                checksum ^= closures[index % 4](index as u64);
+            }
+        }
+    }
+
+    checksum.wrapping_mul(1_099_511_628_211) ^ acc.get()
+}
+
+// Flood fill of an `n` x `n` grid from the top-left corner with an explicit
+// stack. Cells are generated row-major, a cell being a wall when
+// `rng % 100 < 35`; the corner is always open. A cell is marked when pushed,
+// and each popped cell pushes its open, unmarked neighbours in the order
+// up, down, left, right. The checksum folds every popped cell index in pop
+// order together with the filled-cell count.
+fn run_flood_fill(n: usize, seed: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    let mut rng = InputRng::new(seed);
+    let mut wall: Vec<bool> = (0..n * n).map(|_| rng.next() % 100 < 35).collect();
+    wall[0] = false;
+
+    let mut filled = vec![false; n * n];
+    let mut stack = vec![0usize];
+    filled[0] = true;
+    let mut count = 0u64;
+    let mut checksum = 0u64;
+    while let Some(cell) = stack.pop() {
+        count += 1;
+        checksum = checksum.wrapping_mul(1_099_511_628_211).wrapping_add(cell as u64);
+        let (row, col) = (cell / n, cell % n);
+        let neighbours = [
+            (row > 0).then(|| cell - n),
+            (row + 1 < n).then(|| cell + n),
+            (col > 0).then(|| cell - 1),
+            (col + 1 < n).then(|| cell + 1),
+        ];
+        for next in neighbours.iter().flatten() {
+            if !wall[*next] && !filled[*next] {
+                filled[*next] = true;
+                stack.push(*next);
+            }
+        }
+    }
+
+    checksum ^ (count << 40)
+}
+
+// A lazy map/filter/fold pipeline over `0..n`: each index is mixed with the
+// seed, values divisible by 3 are dropped, and the rest are folded with a
+// multiply-add. `iterator_pipeline_loop` is the same computation written as
+// a plain loop and must return the same value.
+fn run_iterator_pipeline(n: u64, seed: u64) -> u64 {
+    (0..n)
+        .map(|index| index.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ seed)
+        .filter(|value| value % 3 != 0)
+        .map(|value| value >> 7)
+        .fold(0u64, |checksum, value| checksum.wrapping_mul(31).wrapping_add(value))
+}
+
+#[cfg(test)]
+fn iterator_pipeline_loop(n: u64, seed: u64) -> u64 {
+    let mut checksum = 0u64;
+    for index in 0..n {
+        let value = index.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ seed;
+        if value.is_multiple_of(3) {
+            continue;
+        }
+        checksum = checksum.wrapping_mul(31).wrapping_add(value >> 7);
+    }
+    checksum
+}
+
+// Longest common subsequence of two length-`n` sequences over the alphabet
+// `lcg % 4` (all of `a` is drawn, then all of `b`), using the full
+// `(n + 1) x (n + 1)` table `t[i][j] = t[i-1][j-1] + 1` on a match and
+// `max(t[i-1][j], t[i][j-1])` otherwise. Folds the LCS length with the
+// table sample `t[n / 2][n / 2]`.
+fn run_lcs(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let a: Vec<u8> = (0..n).map(|_| (rng.next() % 4) as u8).collect();
+    let b: Vec<u8> = (0..n).map(|_| (rng.next() % 4) as u8).collect();
+
+    let width = n + 1;
+    let mut table = vec![0u32; width * width];
+    for i in 1..=n {
+        for j in 1..=n {
+            table[i * width + j] = if a[i - 1] == b[j - 1] {
+                table[(i - 1) * width + j - 1] + 1
+            } else {
+                table[(i - 1) * width + j].max(table[i * width + j - 1])
+            };
+        }
+    }
+
+    let length = u64::from(table[n * width + n]);
+    let sample = u64::from(table[(n / 2) * width + n / 2]);
+    (length << 32) ^ sample
+}
+
+// `tail_sum(k, acc) = tail_sum(k - 1, acc * 31 + (k ^ seed))`, ending with
+// `acc` at `k = 0`; `run_tail_sum` starts it at `(n, 0)`. The recursive call
+// is in tail position, so a language with tail-call elimination runs it in
+// constant stack; without it every step is a stack frame and the default
+// size of 5,000,000 overflows a typical 8 MiB stack. rustc -O turns it into a
+// loop, but Rust does not guarantee that, and unoptimized builds recurse.
+fn tail_sum(k: u64, acc: u64, seed: u64) -> u64 {
+    if k == 0 {
+        acc
+    } else {
+        tail_sum(k - 1, acc.wrapping_mul(31).wrapping_add(k ^ seed), seed)
+    }
+}
+
+// Generous bound on one unoptimized `tail_sum` frame (about 100 bytes on
+// x86-64), for sizing the stack of the thread that runs it.
+const TAIL_SUM_FRAME_BYTES: u64 = 256;
+
+// Builds with debug assertions, which are normally unoptimized, run the
+// recursion on a thread whose stack fits `n` frames, so the default size
+// does not crash them; their timings include starting that thread on every
+// iteration. Optimized builds call it directly and time only the fold.
+fn run_tail_sum(n: u64, seed: u64) -> u64 {
+    if !cfg!(debug_assertions) {
+        return tail_sum(n, 0, seed);
+    }
+    let stack_bytes = n.saturating_mul(TAIL_SUM_FRAME_BYTES).saturating_add(1 << 20);
+    let spawned = thread::Builder::new()
+        .stack_size(stack_bytes as usize)
+        .spawn(move || tail_sum(n, 0, seed));
+    match spawned {
+        Ok(handle) => handle.join().expect("The tail_sum thread panicked."),
+        Err(spawn_error) => {
+            error!(
+                "could not start a thread with a {} byte stack for tail_sum at --tail-n {} ({}); use an optimized build (rustc -O) or a smaller --tail-n.",
+                stack_bytes, n, spawn_error
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+fn tail_sum_loop(n: u64, seed: u64) -> u64 {
+    let mut acc = 0u64;
+    for k in (1..=n).rev() {
+        acc = acc.wrapping_mul(31).wrapping_add(k ^ seed);
+    }
+    acc
+}
+
+// Insertion `i` (0-based, array length `i` before it) draws the position
+// `lcg % (i + 1)` and then the value `lcg`, and inserts it there, shifting
+// everything after it up by one as a memmove. The checksum folds the final
+// array front to back with `acc * 31 + value`, plus the length.
+fn run_gap_shift(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut values: Vec<u32> = Vec::with_capacity(n);
+    for i in 0..n {
+        let position = (rng.next() % (i as u64 + 1)) as usize;
+        let value = rng.next() as u32;
+        values.insert(position, value);
+    }
+
+    let mut acc = values.len() as u64;
+    for &value in &values {
+        acc = acc.wrapping_mul(31).wrapping_add(u64::from(value));
+    }
+    acc
+}
+
+// Appends `value` in decimal, left-padded with zeros to at least `width`.
+fn push_decimal(text: &mut Vec<u8>, value: u64, width: usize) {
+    let mut digits = [0u8; 20];
+    let mut len = 0;
+    let mut rest = value;
+    while rest > 0 || len < width.max(1) {
+        digits[len] = b'0' + (rest % 10) as u8;
+        rest /= 10;
+        len += 1;
+    }
+    text.extend(digits[..len].iter().rev());
+}
+
+// The text is `n` numbers separated by single spaces. Each number draws
+// `r = lcg`, writes `-` when `r` is odd, and then by `(r >> 1) % 4`:
+//   0, 1: an integer `lcg % 1000000`
+//   2:    a decimal `lcg % 100000` `.` `lcg % 1000` padded to three digits
+//   3:    an exponent form `lcg % 10` `.` `lcg % 100` padded to two digits,
+//         `e`, `-` when `lcg` is odd else `+`, then `lcg % 300`
+// drawing the fields left to right. The tokenizer accepts
+// `-? digit+ ('.' digit+)? ([eE] [+-]? digit+)?` and classifies a token as a
+// float when it has a fraction or an exponent. Each token folds into
+// `acc = acc * 31 + x`, with `x` the signed value (two's complement) for an
+// integer and `digit_count * 2 + 1` for a float; the result is
+// `acc ^ (integers << 32) ^ floats`.
+fn run_tokenize_numbers(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut text = Vec::with_capacity(n * 10);
+    for index in 0..n {
+        if index > 0 {
+            text.push(b' ');
+        }
+        let r = rng.next();
+        if r & 1 == 1 {
+            text.push(b'-');
+        }
+        match (r >> 1) % 4 {
+            0 | 1 => push_decimal(&mut text, rng.next() % 1_000_000, 1),
+            2 => {
+                push_decimal(&mut text, rng.next() % 100_000, 1);
+                text.push(b'.');
+                push_decimal(&mut text, rng.next() % 1_000, 3);
+            }
+            _ => {
+                push_decimal(&mut text, rng.next() % 10, 1);
+                text.push(b'.');
+                push_decimal(&mut text, rng.next() % 100, 2);
+                text.push(b'e');
+                text.push(if rng.next() & 1 == 1 { b'-' } else { b'+' });
+                push_decimal(&mut text, rng.next() % 300, 1);
+            }
+        }
+    }
+
+    let is_digit = |i: usize| i < text.len() && text[i].is_ascii_digit();
+    let mut acc = 0u64;
+    let mut integers = 0u64;
+    let mut floats = 0u64;
+    let mut i = 0;
+    while i < text.len() {
+        if text[i] == b' ' {
+            i += 1;
+            continue;
+        }
+        let negative = text[i] == b'-';
+        if negative {
+            i += 1;
+        }
+        let mut value = 0u64;
+        let mut digits = 0u64;
+        while is_digit(i) {
+            value = value * 10 + u64::from(text[i] - b'0');
+            digits += 1;
+            i += 1;
+        }
+        let mut float = false;
+        if i < text.len() && text[i] == b'.' {
+            float = true;
+            i += 1;
+            while is_digit(i) {
+                digits += 1;
+                i += 1;
+            }
+        }
+        if i < text.len() && (text[i] == b'e' || text[i] == b'E') {
+            float = true;
+            i += 1;
+            if i < text.len() && (text[i] == b'+' || text[i] == b'-') {
+                i += 1;
+            }
+            while is_digit(i) {
+                digits += 1;
+                i += 1;
+            }
+        }
+
+        let x = if float {
+            floats += 1;
+            digits * 2 + 1
+        } else {
+            integers += 1;
+            if negative {
+                value.wrapping_neg()
+            } else {
+                value
+            }
+        };
+        acc = acc.wrapping_mul(31).wrapping_add(x);
+    }
+    acc ^ (integers << 32) ^ floats
+}
+
+// `acc += addend` with base-2^32 limbs, least significant first; `acc` grows
+// by a limb when the carry runs off its end.
+fn bignum_add_assign(acc: &mut Vec<u32>, addend: &[u32]) {
+    let mut carry = 0u64;
+    for i in 0..acc.len().max(addend.len()) {
+        if i == acc.len() {
+            acc.push(0);
+        }
+        let sum = u64::from(acc[i]) + u64::from(addend.get(i).copied().unwrap_or(0)) + carry;
+        acc[i] = sum as u32;
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        acc.push(carry as u32);
+    }
+}
+
+// Two operands of `digits` limbs, least significant limb first, each limb
+// `(lcg << 16) ^ lcg` truncated to 32 bits; all of `a` is drawn before `b`.
+// `a` then has `b` added to it `reps` times, and the sum's limbs fold into
+// `acc * 31 + limb` from the least significant end, starting at its length.
+fn run_bignum_add(digits: usize, reps: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut limb = || ((rng.next() << 16) ^ rng.next()) as u32;
+    let mut sum: Vec<u32> = (0..digits).map(|_| limb()).collect();
+    let addend: Vec<u32> = (0..digits).map(|_| limb()).collect();
+    for _ in 0..reps {
+        bignum_add_assign(&mut sum, &addend);
+    }
+
+    let mut acc = sum.len() as u64;
+    for limb in &sum {
+        acc = acc.wrapping_mul(31).wrapping_add(u64::from(*limb));
+    }
+    acc
+}
+
+// `n` strictly increasing values starting at `1 + lcg % 4`, each adding a
+// step of `1 + lcg % 4` to the previous one.
+fn sorted_set(n: usize, rng: &mut InputRng) -> Vec<u64> {
+    let mut value = 0u64;
+    (0..n)
+        .map(|_| {
+            value += 1 + rng.next() % 4;
+            value
+        })
+        .collect()
+}
+
+// Two `sorted_set`s of `n` values, all of `a` drawn before `b`, so about
+// two in five values are shared. Merge walks build the union and the
+// intersection in increasing order; the checksum folds `acc * 31 + value`
+// (wrapping) over the union and then the intersection, and xors in
+// `(union_len << 40) ^ (intersection_len << 20)`.
+fn run_set_ops(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let a = sorted_set(n, &mut rng);
+    let b = sorted_set(n, &mut rng);
+
+    let mut union = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            union.push(a[i]);
+            i += 1;
+        } else if b[j] < a[i] {
+            union.push(b[j]);
+            j += 1;
+        } else {
+            union.push(a[i]);
+            i += 1;
+            j += 1;
+        }
+    }
+    union.extend_from_slice(&a[i..]);
+    union.extend_from_slice(&b[j..]);
+
+    let mut intersection = Vec::with_capacity(a.len().min(b.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            i += 1;
+        } else if b[j] < a[i] {
+            j += 1;
+        } else {
+            intersection.push(a[i]);
+            i += 1;
+            j += 1;
+        }
+    }
+
+    let hash = union
+        .iter()
+        .chain(&intersection)
+        .fold(0u64, |acc, &value| acc.wrapping_mul(31).wrapping_add(value));
+    hash ^ ((union.len() as u64) << 40) ^ ((intersection.len() as u64) << 20)
+}
+
+const REGEX_PATTERN: &[u8] = b"b.c*a.*dd";
+
+// Rob Pike's backtracking matcher: literal bytes, `.` for any byte, `c*` for
+// zero or more of `c`, and `^`/`$` anchors. `*` tries the shortest run
+// first, but only whether and where a match starts is reported, so a
+// greedy port gets the same answers.
+fn regex_match_here(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => true,
+        [b'$'] => text.is_empty(),
+        [c, b'*', rest @ ..] => {
+            let mut text = text;
+            loop {
+                if regex_match_here(rest, text) {
+                    return true;
+                }
+                match text.first() {
+                    Some(byte) if *c == b'.' || byte == c => text = &text[1..],
+                    _ => return false,
+                }
+            }
+        }
+        [c, rest @ ..] => match text.first() {
+            Some(byte) if *c == b'.' || byte == c => regex_match_here(rest, &text[1..]),
+            _ => false,
+        },
+    }
+}
+
+// The leftmost position where `pattern` matches, trying every start from 0
+// through `text.len()` unless the pattern begins with `^`.
+fn regex_find(pattern: &[u8], text: &[u8]) -> Option<usize> {
+    if let Some(rest) = pattern.strip_prefix(b"^") {
+        return if regex_match_here(rest, text) { Some(0) } else { None };
+    }
+    (0..=text.len()).find(|&start| regex_match_here(pattern, &text[start..]))
+}
+
+// Input `i` has length `1 + lcg % 32` and then that many bytes
+// `b"abcd"[lcg % 4]`. Each string folds `acc * 31 + (start + 1)` for a match
+// of REGEX_PATTERN at `start`, or `acc * 31` for none; the result is
+// `acc ^ (matches << 32)`.
+fn run_regex_match(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut text = Vec::with_capacity(32);
+    let mut acc = 0u64;
+    let mut matches = 0u64;
+    for _ in 0..n {
+        let len = 1 + (rng.next() % 32) as usize;
+        text.clear();
+        text.extend((0..len).map(|_| b"abcd"[(rng.next() % 4) as usize]));
+        let found = match regex_find(REGEX_PATTERN, &text) {
+            Some(start) => {
+                matches += 1;
+                start as u64 + 1
+            }
+            None => 0,
+        };
+        acc = acc.wrapping_mul(31).wrapping_add(found);
+    }
+    acc ^ (matches << 32)
+}
+
+// The shapes of `vtable_loop`; areas are integers so every port agrees.
+trait Shape {
+    fn area(&self) -> u64;
+}
+
+struct Square {
+    side: u64,
+}
+
+struct Rectangle {
+    width: u64,
+    height: u64,
+}
+
+struct Triangle {
+    base: u64,
+    height: u64,
+}
+
+struct Circle {
+    radius: u64,
+}
+
+impl Shape for Square {
+    fn area(&self) -> u64 {
+        self.side * self.side
+    }
+}
+
+impl Shape for Rectangle {
+    fn area(&self) -> u64 {
+        self.width * self.height
+    }
+}
+
+impl Shape for Triangle {
+    fn area(&self) -> u64 {
+        self.base * self.height / 2
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> u64 {
+        self.radius * self.radius * 355 / 113
+    }
+}
+
+const VTABLE_SHAPES: usize = 64;
+const VTABLE_SEQUENCE: usize = 1024;
+
+// Builds VTABLE_SHAPES boxed shapes: shape `i` draws `kind = lcg % 4`
+// (square, rectangle, triangle, circle) and then one dimension `lcg % 1000 + 1`
+// per field in declaration order. Then VTABLE_SEQUENCE indices `lcg % 64`
+// are drawn, and call `i` of `n` is `shapes[sequence[i % 1024]].area()`,
+// folded as `acc * 31 + area`. Triangle and circle areas truncate:
+// `base * height / 2` and `radius^2 * 355 / 113`.
+fn run_vtable_loop(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut shapes: Vec<Box<dyn Shape>> = Vec::with_capacity(VTABLE_SHAPES);
+    for _ in 0..VTABLE_SHAPES {
+        let kind = rng.next() % 4;
+        let mut dimension = || rng.next() % 1000 + 1;
+        shapes.push(match kind {
+            0 => Box::new(Square { side: dimension() }),
+            1 => Box::new(Rectangle {
+                width: dimension(),
+                height: dimension(),
+            }),
+            2 => Box::new(Triangle {
+                base: dimension(),
+                height: dimension(),
+            }),
+            _ => Box::new(Circle { radius: dimension() }),
+        });
+    }
+    let sequence: Vec<usize> = (0..VTABLE_SEQUENCE).map(|_| (rng.next() % VTABLE_SHAPES as u64) as usize).collect();
+
+    let mut acc = 0u64;
+    for i in 0..n {
+        acc = acc.wrapping_mul(31).wrapping_add(shapes[sequence[i % VTABLE_SEQUENCE]].area());
+    }
+    acc
+}
+
+// Single-source shortest paths from node 0 over an undirected weighted
+// pseudo-random graph. For each node `u` in order, `degree` edges each
+// draw `v = lcg % nodes` and then `weight = lcg % 100 + 1`, and are appended
+// to the adjacency lists of both `u` and `v`. A binary min-heap of
+// `(distance, node)` with lazy deletion pops the smaller node on equal
+// distances. Shortest distances are unique anyway, so the checksum, the
+// distances folded in node order as `acc * 31 + d` with `u32::MAX` for
+// unreachable nodes, does not depend on tie-breaking.
+fn run_dijkstra(nodes: usize, degree: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut adjacency: Vec<Vec<(u32, u32)>> = vec![Vec::new(); nodes];
+    for u in 0..nodes {
+        for _ in 0..degree {
+            let v = rng.next() as usize % nodes;
+            let weight = (rng.next() % 100 + 1) as u32;
+            adjacency[u].push((v as u32, weight));
+            adjacency[v].push((u as u32, weight));
+        }
+    }
+
+    let mut distance = vec![u32::MAX; nodes];
+    let mut heap = BinaryHeap::new();
+    if nodes > 0 {
+        distance[0] = 0;
+        heap.push(Reverse((0u32, 0u32)));
+    }
+    while let Some(Reverse((dist, node))) = heap.pop() {
+        let node = node as usize;
+        if dist > distance[node] {
+            continue;
+        }
+        for &(next, weight) in &adjacency[node] {
+            let candidate = dist + weight;
+            if candidate < distance[next as usize] {
+                distance[next as usize] = candidate;
+                heap.push(Reverse((candidate, next)));
+            }
+        }
+    }
+
+    distance
+        .iter()
+        .fold(0u64, |acc, dist| acc.wrapping_mul(31).wrapping_add(u64::from(*dist)))
+}
+
+// Evaluates `p(x) = sum c_k x^k` with `c_k = (-1)^k / (k + 1)` for
+// `k = 0..=degree` by Horner's rule, `p = p * x + c_k` from `c_degree` down,
+// at `reps` points `x = rng_unit`. Each result is quantized to 1e-9 and the
+// quantized values are summed (wrapping), so a port that fuses the
+// multiply-add and rounds a point differently moves the sum by a few units
+// instead of scrambling it; the registry tolerance covers that.
+fn run_horner(degree: usize, reps: usize, seed: u64) -> u64 {
+    let coefficients: Vec<f64> = (0..=degree)
+        .map(|k| if k % 2 == 0 { 1.0 } else { -1.0 } / (k + 1) as f64)
+        .collect();
+    let mut rng = InputRng::new(seed);
+    let mut sum = 0u64;
+    for _ in 0..reps {
+        let x = rng_unit(&mut rng);
+        let mut p = coefficients[degree];
+        for coefficient in coefficients[..degree].iter().rev() {
+            p = p * x + coefficient;
+        }
+        sum = sum.wrapping_add((p * 1e9).round() as i64 as u64);
+    }
+    sum
+}
+
+const REFCOUNT_SLOTS: usize = 64;
+
+// Step `i` draws `r = lcg`; slot `(r >> 8) % 64` of a pool of handles is
+// emptied when `r % 3 == 0` and otherwise set to a new clone of the root,
+// dropping whatever it held. Each step folds `acc * 31 + (count ^ value)`,
+// with `count` the root's strong count and `value` the shared `seed`.
+fn refcount_churn<P: Clone + std::ops::Deref<Target = u64>>(
+    root: P,
+    count: fn(&P) -> usize,
+    n: usize,
+    seed: u64,
+) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut slots: Vec<Option<P>> = vec![None; REFCOUNT_SLOTS];
+    let mut acc = 0u64;
+    for _ in 0..n {
+        let r = rng.next();
+        let slot = ((r >> 8) % REFCOUNT_SLOTS as u64) as usize;
+        slots[slot] = if r.is_multiple_of(3) { None } else { Some(root.clone()) };
+        acc = acc.wrapping_mul(31).wrapping_add(count(&root) as u64 ^ *root);
+    }
+    acc
+}
+
+// Measures the runtime's sharing model rather than arithmetic: `rc` pays
+// plain increments and decrements, `arc` atomic ones. Both give the same
+// checksum.
+fn run_refcount_churn(n: usize, mode: RcMode, seed: u64) -> u64 {
+    match mode {
+        RcMode::Rc => refcount_churn(Rc::new(seed), Rc::strong_count, n, seed),
+        RcMode::Arc => refcount_churn(Arc::new(seed), Arc::strong_count, n, seed),
+    }
+}
+
+// `y = A x` repeated `reps` times over an `n` x `n` matrix with the
+// `affine_grid` entries `A[row][k] = (row * 131 + k * 17 + 13) % 256`,
+// stored row-major. The first `x` is `n` draws `lcg % 256`; each later rep
+// uses `x_k = y_k % 255 + 1` of the previous one (reducing modulo 256 makes
+// the iteration collapse to zero within a few reps). Sums wrap at 2^64, and
+// the last `y` folds in row order as `acc * 31 + y_row`.
+fn run_matvec(n: usize, reps: usize, seed: u64) -> u64 {
+    let matrix: Vec<u64> = (0..n * n)
+        .map(|index| (((index / n) * 131 + (index % n) * 17 + 13) % 256) as u64)
+        .collect();
+    let mut rng = InputRng::new(seed);
+    let mut x: Vec<u64> = (0..n).map(|_| rng.next() % 256).collect();
+    let mut y = vec![0u64; n];
+    for rep in 0..reps {
+        if rep > 0 {
+            for (x, y) in x.iter_mut().zip(&y) {
+                *x = y % 255 + 1;
+            }
+        }
+        for (row, y) in y.iter_mut().enumerate() {
+            *y = matrix[row * n..(row + 1) * n]
+                .iter()
+                .zip(&x)
+                .fold(0u64, |acc, (a, x)| acc.wrapping_add(a.wrapping_mul(*x)));
+        }
+    }
+
+    y.iter().fold(0u64, |acc, y| acc.wrapping_mul(31).wrapping_add(*y))
+}
+
+// Kahn's algorithm over a pseudo-random DAG. The nodes first get labels from
+// a Fisher-Yates shuffle of `0..nodes` (`swap(i, lcg % (i + 1))` for `i`
+// from `nodes - 1` down to 1); then each of `edges` draws `u = lcg % nodes`
+// and `v = lcg % nodes` and, unless `u == v`, points from the endpoint with
+// the smaller label to the other, duplicates included. Ready nodes leave a
+// min-heap lowest index first, and the order folds as `acc * 31 + node`,
+// finishing with the number of nodes placed; a graph without nodes gives 0.
+fn run_toposort(nodes: usize, edges: usize, seed: u64) -> u64 {
+    if nodes == 0 {
+        return 0;
+    }
+    let mut rng = InputRng::new(seed);
+    let mut label: Vec<u32> = (0..nodes as u32).collect();
+    for i in (1..nodes).rev() {
+        let j = (rng.next() % (i as u64 + 1)) as usize;
+        label.swap(i, j);
+    }
+
+    let mut successors: Vec<Vec<u32>> = vec![Vec::new(); nodes];
+    let mut indegree = vec![0u32; nodes];
+    for _ in 0..edges {
+        let u = rng.next() as usize % nodes;
+        let v = rng.next() as usize % nodes;
+        if u == v {
+            continue;
+        }
+        let (from, to) = if label[u] < label[v] { (u, v) } else { (v, u) };
+        successors[from].push(to as u32);
+        indegree[to] += 1;
+    }
+
+    let mut ready: BinaryHeap<Reverse<u32>> = (0..nodes as u32)
+        .filter(|node| indegree[*node as usize] == 0)
+        .map(Reverse)
+        .collect();
+    let mut acc = 0u64;
+    let mut placed = 0u64;
+    while let Some(Reverse(node)) = ready.pop() {
+        acc = acc.wrapping_mul(31).wrapping_add(u64::from(node));
+        placed += 1;
+        for &next in &successors[node as usize] {
+            indegree[next as usize] -= 1;
+            if indegree[next as usize] == 0 {
+                ready.push(Reverse(next));
+            }
+        }
+    }
+    acc.wrapping_mul(31).wrapping_add(placed)
+}
+
+// A random recursive tree in CSR form: node `i` for `i = 1..n` hangs under
+// `lcg % i`, and every node's children are listed in increasing index order
+// in `children[offsets[node]..offsets[node + 1]]`.
+fn dfs_tree(n: usize, seed: u64) -> (Vec<usize>, Vec<u32>) {
+    let mut rng = InputRng::new(seed);
+    let parents: Vec<usize> = (1..n).map(|i| (rng.next() % i as u64) as usize).collect();
+    let mut offsets = vec![0usize; n + 1];
+    for &parent in &parents {
+        offsets[parent + 1] += 1;
+    }
+    for node in 0..n {
+        offsets[node + 1] += offsets[node];
+    }
+    let mut next = offsets.clone();
+    let mut children = vec![0u32; parents.len()];
+    for (index, &parent) in parents.iter().enumerate() {
+        children[next[parent]] = index as u32 + 1;
+        next[parent] += 1;
+    }
+    (offsets, children)
+}
+
+fn dfs_visit(acc: u64, node: u32, depth: u32) -> u64 {
+    acc.wrapping_mul(31).wrapping_add((u64::from(node) << 8) ^ u64::from(depth))
+}
+
+// Preorder DFS from node 0 over `dfs_tree`, visiting children in increasing
+// index order and folding `acc * 31 + ((node << 8) ^ depth)` per visit. It
+// keeps its own stack, pushing children in reverse so the first is popped
+// first; a unit test checks it against the recursive traversal, which ports
+// without deep recursion can skip.
+fn run_explicit_stack_dfs(n: usize, seed: u64) -> u64 {
+    let (offsets, children) = dfs_tree(n, seed);
+    let mut stack: Vec<(u32, u32)> = Vec::new();
+    if n > 0 {
+        stack.push((0, 0));
+    }
+    let mut acc = 0u64;
+    while let Some((node, depth)) = stack.pop() {
+        acc = dfs_visit(acc, node, depth);
+        let node = node as usize;
+        for &child in children[offsets[node]..offsets[node + 1]].iter().rev() {
+            stack.push((child, depth + 1));
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+fn dfs_recursive(offsets: &[usize], children: &[u32], node: u32, depth: u32, acc: u64) -> u64 {
+    let mut acc = dfs_visit(acc, node, depth);
+    let node = node as usize;
+    for &child in &children[offsets[node]..offsets[node + 1]] {
+        acc = dfs_recursive(offsets, children, child, depth + 1, acc);
+    }
+    acc
+}
+
+// The zero-work floor: its row shows what the timing loop, the call through
+// the registry, and mix_checksum cost on their own. black_box keeps the
+// constant from being folded into the loop.
+fn run_noop() -> u64 {
+    std::hint::black_box(0)
+}
+
+// Combines a left and right child hash; the rotation makes it order-dependent.
+fn merkle_combine(left: u64, right: u64) -> u64 {
+    splitmix64_mix(left.rotate_left(17) ^ right)
+}
+
+// Leaf `i` is `splitmix64_mix(lcg)`. Each level pairs nodes `2j` and
+// `2j + 1` into `merkle_combine(left, right)`; an odd last node moves up
+// unchanged. The checksum is the root, or 0 without leaves.
+fn run_merkle(leaves: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut level: Vec<u64> = (0..leaves).map(|_| splitmix64_mix(rng.next())).collect();
+    while level.len() > 1 {
+        let parents = level.len().div_ceil(2);
+        for j in 0..parents {
+            level[j] = match level.get(2 * j + 1) {
+                Some(&right) => merkle_combine(level[2 * j], right),
+                None => level[2 * j],
+            };
+        }
+        level.truncate(parents);
+    }
+    level.first().copied().unwrap_or(0)
+}
+
+// Conway's Game of Life on an `n` x `n` torus: the grid is seeded row-major
+// with a cell alive when `lcg % 100 < 30`, then advanced `steps`
+// generations into a second buffer. Neighbours wrap around all four edges.
+// The checksum folds the row-major index of every live cell of the final
+// generation and the live count.
+fn run_life(n: usize, steps: usize, seed: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    let mut rng = InputRng::new(seed);
+    let mut grid: Vec<u8> = (0..n * n).map(|_| (rng.next() % 100 < 30) as u8).collect();
+    let mut next = vec![0u8; n * n];
+
+    for _ in 0..steps {
+        for row in 0..n {
+            let up = if row == 0 { n - 1 } else { row - 1 } * n;
+            let here = row * n;
+            let down = if row + 1 == n { 0 } else { row + 1 } * n;
+            for col in 0..n {
+                let left = if col == 0 { n - 1 } else { col - 1 };
+                let right = if col + 1 == n { 0 } else { col + 1 };
+                let live = grid[up + left]
+                    + grid[up + col]
+                    + grid[up + right]
+                    + grid[here + left]
+                    + grid[here + right]
+                    + grid[down + left]
+                    + grid[down + col]
+                    + grid[down + right];
+                next[here + col] = (live == 3 || (live == 2 && grid[here + col] == 1)) as u8;
+            }
+        }
+        std::mem::swap(&mut grid, &mut next);
+    }
+
+    let mut count = 0u64;
+    let mut checksum = 0u64;
+    for (cell, alive) in grid.iter().enumerate() {
+        if *alive == 1 {
+            count += 1;
+            checksum = checksum.wrapping_mul(1_099_511_628_211).wrapping_add(cell as u64);
+        }
+    }
+    checksum ^ (count << 40)
+}
+
+const ARRAY_SUM_PASSES: u64 = 32;
+
+// Sums `n` values `lcg` by index, ARRAY_SUM_PASSES times, XORing each
+// value with the pass number and folding each pass as `acc * 31 + sum`.
+// The length is hidden behind black_box so the compiler cannot prove the
+// `checked` indices in range and drop their bounds checks; `unchecked`
+// reads the same elements with get_unchecked, so both modes return the
+// same checksum and differ only by the checks. The index loops are the
+// point, so clippy's iterator suggestion does not apply.
+#[allow(clippy::needless_range_loop)]
+fn run_array_sum(n: usize, bounds: BoundsMode, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let values: Vec<u64> = (0..n).map(|_| rng.next()).collect();
+    let len = std::hint::black_box(n);
+
+    let mut acc = 0u64;
+    for pass in 0..ARRAY_SUM_PASSES {
+        let mut sum = 0u64;
+        match bounds {
+            BoundsMode::Checked => {
+                for i in 0..len {
+                    sum = sum.wrapping_add(values[i] ^ pass);
+                }
+            }
+            BoundsMode::Unchecked => {
+                for i in 0..len {
+                    // SAFETY: `len` is `n`, the length `values` was built
+                    // with, so every `i` in `0..len` is in bounds.
+                    sum = sum.wrapping_add(unsafe { *values.get_unchecked(i) } ^ pass);
+                }
+            }
+        }
+        acc = acc.wrapping_mul(31).wrapping_add(sum);
+    }
+    acc
+}
+
+// Streams `n` values `lcg` into `buckets` bins, value `v` landing in bin
+// `v % buckets`, then folds the counts in bin order as `acc * 31 + count`.
+fn run_histogram(n: usize, buckets: usize, seed: u64) -> u64 {
+    if buckets == 0 {
+        return 0;
+    }
+    let mut rng = InputRng::new(seed);
+    let mut counts = vec![0u32; buckets];
+    for _ in 0..n {
+        counts[(rng.next() % buckets as u64) as usize] += 1;
+    }
+    counts
+        .iter()
+        .fold(0u64, |acc, &count| acc.wrapping_mul(31).wrapping_add(u64::from(count)))
+}
+
+// Appends `n` chunks to one growing String. Chunk `i` draws `r = lcg` and
+// is `1 + r % 8` lowercase letters, letter `k` being
+// `'a' + (m >> (8 * k)) % 26` with `m = splitmix64_mix(r)`: `r` has only 31
+// bits, so slicing it directly would leave the later letters constant. The
+// checksum is the FNV-1a hash of the final string plus its length in bytes.
+fn run_string_build(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut built = String::new();
+    for _ in 0..n {
+        let r = rng.next();
+        let mixed = splitmix64_mix(r);
+        for k in 0..1 + r % 8 {
+            built.push((b'a' + ((mixed >> (8 * k)) % 26) as u8) as char);
+        }
+    }
+    fnv1a(built.as_bytes()).wrapping_add(built.len() as u64)
+}
+
+const MATCH_PROGRAM_LEN: usize = 256;
+
+#[derive(Clone, Copy)]
+enum MatchOp {
+    Add(u64),
+    Sub(u64),
+    Mul(u64),
+    Xor(u64),
+    RotateLeft(u32),
+    RotateRight(u32),
+    ShiftMix(u32),
+    Swap,
+    AddB,
+    XorB,
+    Not,
+    MulB,
+}
+
+// Applies `n` operations over two registers, `a = seed` and `b = 0`,
+// cycling through a generated 256-op program and dispatching each through
+// one `match` on the enum tag (vm_mix dispatches through function pointers
+// instead). Op `i` draws `r = lcg`, takes its tag from `r % 12` and its
+// operand `k = r >> 8`, in the order: a += k, a -= k, a *= k | 1, a ^= k,
+// a = a.rotl(k % 64), a = a.rotr(k % 64), a ^= a >> (k % 63 + 1), swap a
+// and b, a += b, b ^= a, a = !a, b *= a | 1 (all wrapping). The checksum
+// is `a ^ b.rotl(32)`.
+fn run_match_dispatch(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let program: Vec<MatchOp> = (0..MATCH_PROGRAM_LEN)
+        .map(|_| {
+            let r = rng.next();
+            let k = r >> 8;
+            match r % 12 {
+                0 => MatchOp::Add(k),
+                1 => MatchOp::Sub(k),
+                2 => MatchOp::Mul(k | 1),
+                3 => MatchOp::Xor(k),
+                4 => MatchOp::RotateLeft((k % 64) as u32),
+                5 => MatchOp::RotateRight((k % 64) as u32),
+                6 => MatchOp::ShiftMix((k % 63 + 1) as u32),
+                7 => MatchOp::Swap,
+                8 => MatchOp::AddB,
+                9 => MatchOp::XorB,
+                10 => MatchOp::Not,
+                _ => MatchOp::MulB,
+            }
+        })
+        .collect();
+
+    let (mut a, mut b) = (seed, 0u64);
+    for step in 0..n {
+        match program[step % MATCH_PROGRAM_LEN] {
+            MatchOp::Add(k) => a = a.wrapping_add(k),
+            MatchOp::Sub(k) => a = a.wrapping_sub(k),
+            MatchOp::Mul(k) => a = a.wrapping_mul(k),
+            MatchOp::Xor(k) => a ^= k,
+            MatchOp::RotateLeft(s) => a = a.rotate_left(s),
+            MatchOp::RotateRight(s) => a = a.rotate_right(s),
+            MatchOp::ShiftMix(s) => a ^= a >> s,
+            MatchOp::Swap => std::mem::swap(&mut a, &mut b),
+            MatchOp::AddB => a = a.wrapping_add(b),
+            MatchOp::XorB => b ^= a,
+            MatchOp::Not => a = !a,
+            MatchOp::MulB => b = b.wrapping_mul(a | 1),
+        }
+    }
+    a ^ b.rotate_left(32)
+}
+
+// Builds `n` strictly increasing keys as running sums of `lcg % 8 + 1`,
+// then runs `queries` searches for `lcg % (last key + 1)`, so roughly one
+// query in four is a hit. A hit at index `i` folds `acc * 31 + (i + 1)`,
+// a miss folds `acc * 31`; keys are distinct, so every port finds the
+// same index.
+fn run_binary_search(n: usize, queries: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut key = 0u64;
+    let keys: Vec<u64> = (0..n)
+        .map(|_| {
+            key += rng.next() % 8 + 1;
+            key
+        })
+        .collect();
+
+    let range = key + 1;
+    let mut acc = 0u64;
+    for _ in 0..queries {
+        let found = match keys.binary_search(&(rng.next() % range)) {
+            Ok(index) => index as u64 + 1,
+            Err(_) => 0,
+        };
+        acc = acc.wrapping_mul(31).wrapping_add(found);
+    }
+    acc
+}
+
+#[derive(Clone)]
+struct CloneNode {
+    value: u64,
+    children: Vec<CloneNode>,
+}
+
+// Builds a tree `depth` levels deep (the root is level 1) in which every
+// node above the last level has `width` children, drawing each node's value
+// from the LCG in preorder. Each of `reps` repetitions deep-clones the tree,
+// walks the clone in preorder folding `acc * 31 + value`, and folds that
+// walk into the checksum as `checksum * 31 + (walk ^ rep)`.
+fn run_deep_clone(depth: usize, width: usize, reps: usize, seed: u64) -> u64 {
+    fn build(depth: usize, width: usize, rng: &mut InputRng) -> CloneNode {
+        let value = rng.next();
+        let children = if depth > 1 {
+            (0..width).map(|_| build(depth - 1, width, rng)).collect()
+        } else {
+            Vec::new()
+        };
+        CloneNode { value, children }
+    }
+
+    fn walk(node: &CloneNode, acc: u64) -> u64 {
+        let acc = acc.wrapping_mul(31).wrapping_add(node.value);
+        node.children.iter().fold(acc, |acc, child| walk(child, acc))
+    }
+
+    if depth == 0 {
+        return 0;
+    }
+    let mut rng = InputRng::new(seed);
+    let tree = build(depth, width, &mut rng);
+    let mut checksum = 0u64;
+    for rep in 0..reps {
+        let copy = tree.clone();
+        checksum = checksum.wrapping_mul(31).wrapping_add(walk(&copy, 0) ^ rep as u64);
+    }
+    checksum
+}
+
+// Nodes in a `deep_clone` tree: 1 + width + ... + width^(depth - 1).
+fn clone_tree_nodes(depth: usize, width: usize) -> u64 {
+    let mut nodes = 0u64;
+    let mut level = 1u64;
+    for _ in 0..depth {
+        nodes = nodes.saturating_add(level);
+        level = level.saturating_mul(width as u64);
+    }
+    nodes
+}
+
+// Levenshtein distance between two length-`n` sequences over the alphabet
+// `lcg % 4` (all of `a` is drawn, then all of `b`), with unit-cost
+// insertion, deletion, and substitution: `d[i][j]` is `d[i-1][j-1]` when
+// `a[i-1] == b[j-1]`, otherwise `1 + min(d[i-1][j], d[i][j-1],
+// d[i-1][j-1])`, over `d[i][0] = i` and `d[0][j] = j`. Only two rows are
+// kept. The distance is unique, so tie-breaking cannot change it; the
+// checksum folds it with the last row's middle entry `d[n][n / 2]`.
+fn run_edit_distance(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let a: Vec<u8> = (0..n).map(|_| (rng.next() % 4) as u8).collect();
+    let b: Vec<u8> = (0..n).map(|_| (rng.next() % 4) as u8).collect();
+
+    let mut previous: Vec<u32> = (0..=n as u32).collect();
+    let mut current = vec![0u32; n + 1];
+    for i in 1..=n {
+        current[0] = i as u32;
+        for j in 1..=n {
+            current[j] = if a[i - 1] == b[j - 1] {
+                previous[j - 1]
+            } else {
+                1 + previous[j].min(current[j - 1]).min(previous[j - 1])
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    (u64::from(previous[n]) << 32) ^ u64::from(previous[n / 2])
+}
+
+const EXPR_DEPTH: usize = 4;
+
+// Writes one generated expression at `depth` levels: draw `r = lcg`; at
+// depth 0, or when `r % 4 == 0`, it is the number `(r >> 8) % 100` in
+// decimal. Otherwise it is a left operand, the operator `"+-*"[(r >> 8) %
+// 3]`, and a right operand, each generated at `depth - 1` in that order,
+// wrapped in parentheses when `(r >> 16) % 2 == 0`.
+fn generate_expression(depth: usize, rng: &mut InputRng, out: &mut Vec<u8>) {
+    let r = rng.next();
+    if depth == 0 || r.is_multiple_of(4) {
+        push_decimal(out, (r >> 8) % 100, 1);
+        return;
+    }
+    let parenthesized = (r >> 16).is_multiple_of(2);
+    if parenthesized {
+        out.push(b'(');
+    }
+    generate_expression(depth - 1, rng, out);
+    out.push(b"+-*"[((r >> 8) % 3) as usize]);
+    generate_expression(depth - 1, rng, out);
+    if parenthesized {
+        out.push(b')');
+    }
+}
+
+// Recursive descent over
+//   expr   := term (('+' | '-') term)*
+//   term   := factor ('*' factor)*
+//   factor := digits | '(' expr ')'
+// so `*` binds tighter than `+` and `-`, and all three are left-associative.
+// Values are u64 with wrapping arithmetic.
+struct ExprParser<'a> {
+    text: &'a [u8],
+    position: usize,
+}
+
+impl ExprParser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.position).copied()
+    }
+
+    fn expr(&mut self) -> u64 {
+        let mut value = self.term();
+        while let Some(op @ (b'+' | b'-')) = self.peek() {
+            self.position += 1;
+            let right = self.term();
+            value = if op == b'+' { value.wrapping_add(right) } else { value.wrapping_sub(right) };
+        }
+        value
+    }
+
+    fn term(&mut self) -> u64 {
+        let mut value = self.factor();
+        while self.peek() == Some(b'*') {
+            self.position += 1;
+            value = value.wrapping_mul(self.factor());
+        }
+        value
+    }
+
+    fn factor(&mut self) -> u64 {
+        if self.peek() == Some(b'(') {
+            self.position += 1;
+            let value = self.expr();
+            self.position += 1;
+            return value;
+        }
+        let mut value = 0u64;
+        while let Some(digit @ b'0'..=b'9') = self.peek() {
+            value = value * 10 + u64::from(digit - b'0');
+            self.position += 1;
+        }
+        value
+    }
+}
+
+// Generates, parses, and evaluates `n` expressions of EXPR_DEPTH levels one
+// after another from one LCG stream, folding each value as
+// `acc * 31 + value`.
+fn run_expr_eval(n: usize, seed: u64) -> u64 {
+    let mut rng = InputRng::new(seed);
+    let mut text = Vec::new();
+    let mut acc = 0u64;
+    for _ in 0..n {
+        text.clear();
+        generate_expression(EXPR_DEPTH, &mut rng, &mut text);
+        let mut parser = ExprParser {
+            text: &text,
+            position: 0,
+        };
+        acc = acc.wrapping_mul(31).wrapping_add(parser.expr());
+    }
+    acc
+}
+
+const GC_ROOTS: usize = 16;
+
+// Object `i` for `i = 0..objects` holds `lcg % (refs + 1)` references, each
+// to object `lcg % objects` (self-references and duplicates allowed), drawn
+// object by object. The root set is the next `GC_ROOTS` draws `lcg %
+// objects`. The mark phase visits every object reachable from a root once;
+// the checksum is `(marked << 32) ^ sum`, `sum` being the wrapping sum of
+// the marked indices, so the traversal order does not matter.
+fn run_gc_mark(objects: usize, refs: usize, seed: u64) -> u64 {
+    if objects == 0 {
+        return 0;
+    }
+    let mut rng = InputRng::new(seed);
+    let mut offsets = Vec::with_capacity(objects + 1);
+    let mut targets: Vec<u32> = Vec::new();
+    offsets.push(0);
+    for _ in 0..objects {
+        let count = rng.next() % (refs as u64 + 1);
+        for _ in 0..count {
+            targets.push((rng.next() % objects as u64) as u32);
+        }
+        offsets.push(targets.len());
+    }
+
+    let mut marked = vec![false; objects];
+    let mut worklist: Vec<u32> = Vec::new();
+    for _ in 0..GC_ROOTS {
+        worklist.push((rng.next() % objects as u64) as u32);
+    }
+    let mut count = 0u64;
+    let mut sum = 0u64;
+    while let Some(object) = worklist.pop() {
+        let object = object as usize;
+        if marked[object] {
+            continue;
+        }
+        marked[object] = true;
+        count += 1;
+        sum = sum.wrapping_add(object as u64);
+        worklist.extend_from_slice(&targets[offsets[object]..offsets[object + 1]]);
+    }
+    (count << 32) ^ sum
+}
+
+fn mix_checksum(current: u64, value: u64, iteration: u64) -> u64 {
+    let mixed = current
+        ^ value
+            .wrapping_add(0x9e37_79b9_7f4a_7c15)
+            .wrapping_add(iteration << 6)
+            .wrapping_add(iteration >> 2);
+    mixed.rotate_left(13)
+}
+
+struct BenchmarkResult {
+    algorithm: String,
+    iterations: usize,
+    total_ms: f64,
+    checksum: u64,
+    seed: u64,
+    batch: usize,
+    position: usize,
+    // Per-iteration durations; empty unless per-iteration timing is on.
+    samples_ns: Vec<u64>,
+    // Wall-clock start of the measurement in Unix nanoseconds.
+    started_ns: u64,
+    // What one kernel call returns, before mixing across iterations; this is
+    // what --verify compares.
+    value: u64,
+    // User-space instructions retired and cycles over the whole measurement;
+    // zero unless --perf-counters is on.
+    instructions: u64,
+    cycles: u64,
+    // See params_hash; zero for results read back from a file.
+    params_hash: u64,
+    // The kernel's size option as run; zero for results read back.
+    size: u64,
+}
+
+impl BenchmarkResult {
+    fn mean_ms(&self) -> f64 {
+        self.total_ms / self.iterations as f64
+    }
+
+    fn samples_ms(&self) -> Vec<f64> {
+        self.samples_ns.iter().map(|ns| *ns as f64 / 1e6).collect()
+    }
+
+    fn min_ms(&self) -> f64 {
+        self.samples_ms().into_iter().fold(f64::INFINITY, f64::min).min(self.total_ms)
+    }
+
+    fn max_ms(&self) -> f64 {
+        self.samples_ms().into_iter().fold(0.0, f64::max)
+    }
+
+    // Least-squares slope of the samples against their iteration index, in
+    // ms per iteration; zero with fewer than two samples.
+    fn drift_ms_per_iteration(&self) -> f64 {
+        let samples = self.samples_ms();
+        if samples.len() < 2 {
+            return 0.0;
+        }
+        let count = samples.len() as f64;
+        let mean_index = (count - 1.0) / 2.0;
+        let mean = samples.iter().sum::<f64>() / count;
+        let mut covariance = 0.0;
+        let mut variance = 0.0;
+        for (index, sample) in samples.iter().enumerate() {
+            let offset = index as f64 - mean_index;
+            covariance += offset * (sample - mean);
+            variance += offset * offset;
+        }
+        covariance / variance
+    }
+
+    // Sample standard deviation; zero with fewer than two samples.
+    fn stddev_ms(&self) -> f64 {
+        let samples = self.samples_ms();
+        if samples.len() < 2 {
+            return 0.0;
+        }
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance = samples.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>()
+            / (samples.len() - 1) as f64;
+        variance.sqrt()
+    }
+
+    fn ipc(&self) -> f64 {
+        if self.cycles == 0 {
+            0.0
+        } else {
+            self.instructions as f64 / self.cycles as f64
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Column {
+    Language,
+    Algorithm,
+    Iterations,
+    TotalMs,
+    MeanMs,
+    Checksum,
+    Seed,
+    Batch,
+    Position,
+    MinMs,
+    MaxMs,
+    StddevMs,
+    Instructions,
+    Cycles,
+    Ipc,
+    ParamsHash,
+    StartedAt,
+    Size,
+}
+
+impl Column {
+    fn name(self) -> &'static str {
+        match self {
+            Column::Language => "language",
+            Column::Algorithm => "algorithm",
+            Column::Iterations => "iterations",
+            Column::TotalMs => "total_ms",
+            Column::MeanMs => "mean_ms",
+            Column::Checksum => "checksum",
+            Column::Seed => "seed",
+            Column::Batch => "batch",
+            Column::Position => "position",
+            Column::MinMs => "min_ms",
+            Column::MaxMs => "max_ms",
+            Column::StddevMs => "stddev_ms",
+            Column::Instructions => "instructions",
+            Column::Cycles => "cycles",
+            Column::Ipc => "ipc",
+            Column::ParamsHash => "params_hash",
+            Column::StartedAt => "started_at",
+            Column::Size => "size",
+        }
+    }
+
+    // Rust formatting never consults the process locale, so these always use
+    // `.` as the decimal separator and never group digits.
+    fn value(self, result: &BenchmarkResult) -> String {
+        match self {
+            Column::Language => "rust".to_string(),
+            Column::Algorithm => result.algorithm.clone(),
+            Column::Iterations => result.iterations.to_string(),
+            Column::TotalMs => format!("{:.3}", result.total_ms),
+            Column::MeanMs => format!("{:.6}", result.mean_ms()),
+            Column::Checksum => result.checksum.to_string(),
+            Column::Seed => result.seed.to_string(),
+            Column::Batch => result.batch.to_string(),
+            Column::Position => result.position.to_string(),
+            Column::MinMs => format!("{:.6}", result.min_ms()),
+            Column::MaxMs => format!("{:.6}", result.max_ms()),
+            Column::StddevMs => format!("{:.6}", result.stddev_ms()),
+            Column::Instructions => result.instructions.to_string(),
+            Column::Cycles => result.cycles.to_string(),
+            Column::Ipc => format!("{:.3}", result.ipc()),
+            Column::ParamsHash => format!("{:016x}", result.params_hash),
+            Column::StartedAt => iso8601_from_unix(result.started_ns / 1_000_000_000),
+            Column::Size => result.size.to_string(),
+        }
+    }
+
+    fn is_text(self) -> bool {
+        matches!(self, Column::Language | Column::Algorithm | Column::ParamsHash | Column::StartedAt)
+    }
+
+    // Identifiers such as seeds read better without thousands separators.
+    fn is_quantity(self) -> bool {
+        matches!(
+            self,
+            Column::Iterations
+                | Column::TotalMs
+                | Column::MeanMs
+                | Column::Checksum
+                | Column::MinMs
+                | Column::MaxMs
+                | Column::StddevMs
+                | Column::Instructions
+                | Column::Cycles
+                | Column::Ipc
+                | Column::Size
+        )
+    }
+}
+
+const BASE_COLUMNS: [Column; 7] = [
+    Column::Language,
+    Column::Algorithm,
+    Column::Iterations,
+    Column::Size,
+    Column::TotalMs,
+    Column::MeanMs,
+    Column::Checksum,
+];
+
+// `seed` and `batch` only appear when a run produces more than one row per
+// algorithm, `position` only with --shuffle-order, the per-iteration
+// statistics only with --per-iteration, and the hardware counters only with
+// --perf-counters. `size` is always present, so rows from runs at different
+// sizes can be told apart once files are merged.
+fn available_columns(options: &BenchmarkOptions) -> Vec<Column> {
+    let mut columns = BASE_COLUMNS.to_vec();
+    if options.repeat > 1 || options.seed_sweep > 1 {
+        columns.push(Column::Seed);
+        columns.push(Column::Batch);
+    }
+    if options.shuffle_order {
+        columns.push(Column::Position);
+    }
+    if options.per_iteration {
+        columns.extend([Column::MinMs, Column::MaxMs, Column::StddevMs]);
+    }
+    if options.perf_counters {
+        columns.extend([Column::Instructions, Column::Cycles, Column::Ipc]);
+    }
+    if options.parameters_hash {
+        columns.push(Column::ParamsHash);
+    }
+    if options.emit_env {
+        columns.push(Column::StartedAt);
+    }
+    columns
+}
+
+// The available columns, or the subset and order named by --columns. Names
+// are checked against the same list when the options are parsed, so one
+// is only dropped here when --perf-counters fell back after a warning.
+fn active_columns(options: &BenchmarkOptions) -> Vec<Column> {
+    let available = available_columns(options);
+    match &options.columns {
+        Some(list) => list
+            .split(',')
+            .filter_map(|name| available.iter().find(|column| column.name() == name).copied())
+            .collect(),
+        None => available,
+    }
+}
+
+// Quotes a field that contains a comma, quote, or line break, doubling any
+// quotes inside it, or every field with `quote_always`.
+fn csv_field(value: &str, quote_always: bool) -> String {
+    if quote_always || value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_header(columns: &[Column], quote_always: bool) -> String {
+    let names: Vec<String> = columns.iter().map(|column| csv_field(column.name(), quote_always)).collect();
+    names.join(",")
+}
+
+fn csv_row(result: &BenchmarkResult, columns: &[Column], quote_always: bool) -> String {
+    let fields: Vec<String> = columns
+        .iter()
+        .map(|column| csv_field(&column.value(result), quote_always))
+        .collect();
+    fields.join(",")
+}
+
+// Inserts `,` between groups of three digits in the integer part of a plain
+// number such as `12345.678`. Only used for the human-oriented table format.
+fn group_thousands(value: &str) -> String {
+    let (integer, fraction) = match value.find('.') {
+        Some(index) => value.split_at(index),
+        None => (value, ""),
+    };
+
+    let mut grouped = String::with_capacity(value.len() + integer.len() / 3);
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped.push_str(fraction);
+    grouped
+}
+
+// `#` comment lines opening a new seed (outer) or batch (inner) group of
+// rows. Plain runs form a single group and get none.
+fn csv_annotations(
+    previous: Option<&BenchmarkResult>,
+    result: &BenchmarkResult,
+    columns: &[Column],
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !columns.contains(&Column::Batch) {
+        return lines;
+    }
+    let new_seed = previous.is_none_or(|previous| previous.seed != result.seed);
+    if new_seed {
+        lines.push(format!("# seed {}", result.seed));
+    }
+    if new_seed || previous.is_some_and(|previous| previous.batch != result.batch) {
+        lines.push(format!("# batch {}", result.batch));
+    }
+    lines
+}
+
+fn write_csv<W: Write>(
+    out: &mut W,
+    results: &[BenchmarkResult],
+    columns: &[Column],
+    include_header: bool,
+    annotate: bool,
+    quote_always: bool,
+) -> io::Result<()> {
+    if include_header {
+        writeln!(out, "{}", csv_header(columns, quote_always))?;
+    }
+    let mut previous = None;
+    for result in results {
+        if annotate {
+            for line in csv_annotations(previous, result, columns) {
+                writeln!(out, "{}", line)?;
+            }
+        }
+        writeln!(out, "{}", csv_row(result, columns, quote_always))?;
+        previous = Some(result);
+    }
+    Ok(())
+}
+
+// The CSV columns separated by tabs. Nothing is quoted: no column's values
+// contain tabs or line breaks.
+fn write_tsv<W: Write>(
+    out: &mut W,
+    results: &[BenchmarkResult],
+    columns: &[Column],
+    include_header: bool,
+) -> io::Result<()> {
+    if include_header {
+        let names: Vec<&str> = columns.iter().map(|column| column.name()).collect();
+        writeln!(out, "{}", names.join("\t"))?;
+    }
+    for result in results {
+        let cells: Vec<String> = columns.iter().map(|column| column.value(result)).collect();
+        writeln!(out, "{}", cells.join("\t"))?;
+    }
+    Ok(())
+}
+
+fn write_table<W: Write>(
+    out: &mut W,
+    results: &[BenchmarkResult],
+    columns: &[Column],
+    human_numbers: bool,
+) -> io::Result<()> {
+    let rows: Vec<Vec<String>> = results
+        .iter()
+        .map(|result| {
+            columns
+                .iter()
+                .map(|column| {
+                    let value = column.value(result);
+                    if human_numbers && column.is_quantity() {
+                        group_thousands(&value)
+                    } else {
+                        value
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = columns.iter().map(|column| column.name().len()).collect();
+    for row in &rows {
+        for (width, field) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(field.len());
+        }
+    }
+
+    let header: Vec<String> = columns.iter().map(|column| column.name().to_string()).collect();
+    writeln!(out, "{}", format_table_row(&header, columns, &widths))?;
+    for row in &rows {
+        writeln!(out, "{}", format_table_row(row, columns, &widths))?;
+    }
+
+    Ok(())
+}
+
+// Text columns are left-aligned, numeric columns right-aligned.
+fn format_table_row(cells: &[String], columns: &[Column], widths: &[usize]) -> String {
+    let padded: Vec<String> = cells
+        .iter()
+        .zip(columns.iter().zip(widths.iter()))
+        .map(|(cell, (column, width))| {
+            if column.is_text() {
+                format!("{:<width$}", cell, width = width)
+            } else {
+                format!("{:>width$}", cell, width = width)
+            }
+        })
+        .collect();
+    padded.join("  ").trim_end().to_string()
+}
+
+// A GitHub-flavored Markdown table for pasting into issues and pull
+// requests; numeric columns are right-aligned like in the plain table.
+fn write_markdown<W: Write>(
+    out: &mut W,
+    results: &[BenchmarkResult],
+    columns: &[Column],
+) -> io::Result<()> {
+    let header: Vec<&str> = columns.iter().map(|column| column.name()).collect();
+    writeln!(out, "| {} |", header.join(" | "))?;
+    let rules: Vec<&str> = columns
+        .iter()
+        .map(|column| if column.is_text() { "---" } else { "---:" })
+        .collect();
+    writeln!(out, "| {} |", rules.join(" | "))?;
+    for result in results {
+        let cells: Vec<String> = columns.iter().map(|column| column.value(result).replace('|', "\\|")).collect();
+        writeln!(out, "| {} |", cells.join(" | "))?;
+    }
+    Ok(())
+}
+
+// Tag keys and values escape commas, equals signs, and spaces.
+fn influx_escape(value: &str) -> String {
+    value
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+// InfluxDB line protocol. Identifying columns become tags and measured ones
+// fields; integer fields take the `i` suffix, with the u64 checksum stored
+// as its two's-complement i64 since line-protocol integers are signed. A
+// point needs at least one field, so `mean_ms` is added when --columns
+// selects none. Each point is stamped with the start of its batch for its
+// seed, which without --repeat or --seed-sweep is the start of the run.
+fn write_influx<W: Write>(
+    out: &mut W,
+    results: &[BenchmarkResult],
+    columns: &[Column],
+) -> io::Result<()> {
+    let mut batch_started_ns: HashMap<(u64, usize), u64> = HashMap::new();
+    for result in results {
+        let started = batch_started_ns.entry((result.seed, result.batch)).or_insert(u64::MAX);
+        *started = (*started).min(result.started_ns);
+    }
+
+    for result in results {
+        let mut tags = Vec::new();
+        let mut fields = Vec::new();
+        for column in columns {
+            match column {
+                Column::Language | Column::Algorithm | Column::Seed | Column::Batch | Column::ParamsHash => {
+                    tags.push(format!("{}={}", column.name(), influx_escape(&column.value(result))));
+                }
+                Column::Checksum => fields.push(format!("checksum={}i", result.checksum as i64)),
+                Column::StartedAt => fields.push(format!("started_at=\"{}\"", column.value(result))),
+                Column::Iterations | Column::Position | Column::Size => {
+                    fields.push(format!("{}={}i", column.name(), column.value(result)));
+                }
+                _ => fields.push(format!("{}={}", column.name(), column.value(result))),
+            }
+        }
+        if fields.is_empty() {
+            fields.push(format!("mean_ms={}", Column::MeanMs.value(result)));
+        }
+        let mut series = "oaflang_bench".to_string();
+        for tag in &tags {
+            series.push(',');
+            series.push_str(tag);
+        }
+        writeln!(
+            out,
+            "{} {} {}",
+            series,
+            fields.join(","),
+            batch_started_ns[&(result.seed, result.batch)]
+        )?;
+    }
+    Ok(())
+}
+
+// One object per result with the keys in column order, the same order as
+// the CSV header. The checksum is a string because u64 values do not
+// survive parsers that read JSON numbers as doubles.
+fn result_json(result: &BenchmarkResult, columns: &[Column]) -> JsonObject {
+    let mut json = JsonObject::new();
+    for column in columns {
+        if column.is_text() || *column == Column::Checksum {
+            json.string(column.name(), &column.value(result));
+        } else {
+            json.number(column.name(), column.value(result));
+        }
+    }
+    json
+}
+
+// A JSON array with one result object per line, or with `lines` set NDJSON:
+// the bare objects, one per line.
+fn write_json<W: Write>(
+    out: &mut W,
+    results: &[BenchmarkResult],
+    columns: &[Column],
+    lines: bool,
+) -> io::Result<()> {
+    let objects: Vec<String> = results
+        .iter()
+        .map(|result| result_json(result, columns).to_compact_string())
+        .collect();
+    if lines {
+        for object in &objects {
+            writeln!(out, "{}", object)?;
+        }
+    } else if objects.is_empty() {
+        writeln!(out, "[]")?;
+    } else {
+        writeln!(out, "[\n  {}\n]", objects.join(",\n  "))?;
+    }
+    Ok(())
+}
+
+// A gnuplot data block: a `#` comment naming the columns, then one
+// whitespace-separated row per result behind a 0-based `index` column for
+// placing categorical bars. Rows are grouped by algorithm in first-seen
+// order, so the batches of one algorithm sit side by side; the language
+// column is left out since it is always `rust`. When `mean_ms` is present
+// a second comment gives the matching bar-chart `plot` command.
+fn write_gnuplot<W: Write>(
+    out: &mut W,
+    results: &[BenchmarkResult],
+    columns: &[Column],
+) -> io::Result<()> {
+    let columns: Vec<Column> = columns.iter().copied().filter(|column| *column != Column::Language).collect();
+    let mut names = vec!["index"];
+    names.extend(columns.iter().map(|column| column.name()));
+    writeln!(out, "# {}", names.join(" "))?;
+    let field = |column: Column| names.iter().position(|name| *name == column.name()).map(|index| index + 1);
+    if let (Some(mean), Some(algorithm)) = (field(Column::MeanMs), field(Column::Algorithm)) {
+        writeln!(out, "# plot '<file>' using 1:{}:xtic({}) with boxes", mean, algorithm)?;
+    }
+
+    let mut algorithms: Vec<&str> = Vec::new();
+    for result in results {
+        if !algorithms.contains(&result.algorithm.as_str()) {
+            algorithms.push(&result.algorithm);
+        }
+    }
+    let grouped = algorithms
+        .iter()
+        .flat_map(|algorithm| results.iter().filter(move |result| result.algorithm == *algorithm));
+    for (index, result) in grouped.enumerate() {
+        let mut row = vec![index.to_string()];
+        row.extend(columns.iter().map(|column| column.value(result)));
+        writeln!(out, "{}", row.join(" "))?;
+    }
+    Ok(())
+}
+
+fn write_results<W: Write>(
+    out: &mut W,
+    results: &[BenchmarkResult],
+    options: &BenchmarkOptions,
+    include_header: bool,
+) -> io::Result<()> {
+    write_results_as(out, results, options, options.format, include_header)
+}
+
+fn write_results_as<W: Write>(
+    out: &mut W,
+    results: &[BenchmarkResult],
+    options: &BenchmarkOptions,
+    format: OutputFormat,
+    include_header: bool,
+) -> io::Result<()> {
+    let columns = active_columns(options);
+    match format {
+        OutputFormat::Csv => write_csv(
+            out,
+            results,
+            &columns,
+            include_header,
+            options.annotate,
+            options.csv_quote_always,
+        ),
+        OutputFormat::Tsv => write_tsv(out, results, &columns, include_header),
+        OutputFormat::Table => write_table(out, results, &columns, options.human_numbers),
+        OutputFormat::Influx => write_influx(out, results, &columns),
+        OutputFormat::Json => write_json(out, results, &columns, false),
+        OutputFormat::Ndjson => write_json(out, results, &columns, true),
+        OutputFormat::Gnuplot => write_gnuplot(out, results, &columns),
+        OutputFormat::Markdown => write_markdown(out, results, &columns),
+    }
+}
+
+// Writes the results once in every output format, as `results.<extension>`
+// in `dir`, creating it if needed and replacing earlier files.
+fn write_output_dir(dir: &str, results: &[BenchmarkResult], options: &BenchmarkOptions) -> io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for format in OUTPUT_FORMATS {
+        let path = std::path::Path::new(dir).join(format!("results.{}", format.extension()));
+        let mut file = File::create(path)?;
+        write_results_as(&mut file, results, options, format, true)?;
+    }
+    Ok(())
+}
+
+// Runs `write` against stdout and flushes it. A reader that went away
+// early, such as `head`, is not an error: the process exits quietly with
+// status 0 instead of panicking on the broken pipe. Any other failure is.
+fn write_stdout<T>(write: impl FnOnce(&mut io::StdoutLock<'static>) -> io::Result<T>) -> T {
+    let mut out = io::stdout().lock();
+    match write(&mut out).and_then(|value| out.flush().map(|_| value)) {
+        Ok(value) => value,
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => std::process::exit(0),
+        Err(error) => panic!("Failed to write results: {}", error),
+    }
+}
+
+fn read_header(path: &str) -> Option<String> {
+    let file = File::open(path).ok()?;
+    let mut header = String::new();
+    BufReader::new(file).read_line(&mut header).ok()?;
+    let header = header.trim_end();
+    if header.is_empty() {
+        None
+    } else {
+        Some(header.to_string())
+    }
+}
+
+// Opens `--output` for writing and reports whether a CSV header is still
+// needed. When appending to a non-empty file the header is skipped, and the
+// existing header must match this run's columns. Files built with
+// --no-header have no header to compare, so the check is skipped for them.
+fn open_output(path: &str, options: &BenchmarkOptions) -> (File, bool) {
+    let existing_header = if options.append { read_header(path) } else { None };
+    if let Some(header) = &existing_header {
+        if options.format == OutputFormat::Csv
+            && !options.no_header
+            && *header != csv_header(&active_columns(options), options.csv_quote_always)
+        {
+            panic!(
+                "Cannot append to '{}': its columns do not match this run's options.",
+                path
+            );
+        }
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(options.append)
+        .truncate(!options.append)
+        .open(path)
+        .unwrap_or_else(|error| panic!("Failed to open '{}': {}", path, error));
+    (file, existing_header.is_none() && !options.no_header)
+}
+
+// Keeps the rows read from `path` that started at or after `since` (Unix
+// seconds), for --since. Rows without a `started_at` column are dropped
+// with a warning rather than guessed at.
+fn results_since(results: Vec<BenchmarkResult>, since: Option<u64>, path: &str) -> Vec<BenchmarkResult> {
+    let since = match since {
+        Some(since) => since,
+        None => return results,
+    };
+    let undated = results.iter().filter(|result| result.started_ns == 0).count();
+    if undated > 0 {
+        warn!("{} row(s) in {} have no started_at timestamp; --since leaves them out.", undated, path);
+    }
+    results
+        .into_iter()
+        .filter(|result| result.started_ns != 0 && result.started_ns / 1_000_000_000 >= since)
+        .collect()
+}
+
+// Whole days since 1970-01-01 for a proleptic Gregorian date, and back,
+// after Howard Hinnant's `days_from_civil` and `civil_from_days`.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    (era * 400 + year_of_era + i64::from(month <= 2), month, day)
+}
+
+// `YYYY-MM-DDTHH:MM:SSZ` in UTC.
+fn iso8601_from_unix(seconds: u64) -> String {
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let time = seconds % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+// Parses `YYYY-MM-DD` or `YYYY-MM-DDTHH:MM:SS`, with an optional trailing
+// `Z`, as UTC. Returns None for anything else, including impossible dates
+// and times before 1970.
+fn unix_from_iso8601(text: &str) -> Option<u64> {
+    let text = text.strip_suffix('Z').unwrap_or(text);
+    let (date, time) = match text.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (text, None),
+    };
+    let number = |part: &str, digits: usize| -> Option<i64> {
+        if part.len() == digits && part.bytes().all(|byte| byte.is_ascii_digit()) {
+            part.parse().ok()
+        } else {
+            None
+        }
+    };
+    let date: Vec<&str> = date.split('-').collect();
+    let (year, month, day) = match date[..] {
+        [year, month, day] => (number(year, 4)?, number(month, 2)?, number(day, 2)?),
+        _ => return None,
+    };
+    let days = days_from_civil(year, month, day);
+    if !(1..=12).contains(&month) || civil_from_days(days) != (year, month, day) || days < 0 {
+        return None;
+    }
+    let seconds = match time.map(|time| time.split(':').collect::<Vec<&str>>()) {
+        None => 0,
+        Some(parts) => match parts[..] {
+            [hour, minute, second] => {
+                let (hour, minute, second) = (number(hour, 2)?, number(minute, 2)?, number(second, 2)?);
+                if hour > 23 || minute > 59 || second > 59 {
+                    return None;
+                }
+                hour * 3600 + minute * 60 + second
+            }
+            _ => return None,
+        },
+    };
+    Some(days as u64 * 86_400 + seconds as u64)
+}
+
+// Reads result rows back from a CSV written by this harness (or any tool
+// using the same column names). Missing `seed`/`batch` columns mean a single
+// batch for `default_seed`; rows with the wrong field count, such as one cut
+// short by an interrupted run, are skipped. Returns None if the file cannot
+// be opened.
+fn read_results(path: &str, default_seed: u64) -> Option<Vec<BenchmarkResult>> {
+    let file = File::open(path).ok()?;
+    Some(parse_results(BufReader::new(file), path, default_seed))
+}
+
+// Parses results CSV from `reader`; `source` names it in error messages.
+// Quotes around fields, as written by --csv-quote-always, are dropped; no
+// column this harness writes contains a comma.
+fn parse_results<R: BufRead>(reader: R, source: &str, default_seed: u64) -> Vec<BenchmarkResult> {
+    let mut lines = reader.lines();
+    let header = match lines.next() {
+        Some(line) => line.unwrap_or_else(|error| panic!("Failed to read '{}': {}", source, error)),
+        None => return Vec::new(),
+    };
+    let names: Vec<&str> = header.trim_end().split(',').map(|name| name.trim_matches('"')).collect();
+    let position = |name: &str| names.iter().position(|candidate| *candidate == name);
+    let algorithm_index = position("algorithm")
+        .unwrap_or_else(|| panic!("'{}' has no algorithm column.", source));
+    let iterations_index = position("iterations");
+    let size_index = position("size");
+    let total_index = position("total_ms");
+    let checksum_index = position("checksum");
+    let seed_index = position("seed");
+    let batch_index = position("batch");
+    let position_index = position("position");
+    let started_index = position("started_at");
+
+    let mut results = Vec::new();
+    for line in lines {
+        let line = line.unwrap_or_else(|error| panic!("Failed to read '{}': {}", source, error));
+        if line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.trim_end().split(',').map(|field| field.trim_matches('"')).collect();
+        if fields.len() != names.len() {
+            continue;
+        }
+
+        let parsed = (|| {
+            Some(BenchmarkResult {
+                algorithm: fields[algorithm_index].to_string(),
+                iterations: iterations_index.map_or(Some(1), |index| fields[index].parse().ok())?,
+                total_ms: total_index.map_or(Some(0.0), |index| fields[index].parse().ok())?,
+                checksum: checksum_index.map_or(Some(0), |index| fields[index].parse().ok())?,
+                seed: seed_index.map_or(Some(default_seed), |index| fields[index].parse().ok())?,
+                batch: batch_index.map_or(Some(1), |index| fields[index].parse().ok())?,
+                position: position_index.map_or(Some(0), |index| fields[index].parse().ok())?,
+                samples_ns: Vec::new(),
+                started_ns: started_index.map_or(Some(0), |index| {
+                    unix_from_iso8601(fields[index]).map(|seconds| seconds * 1_000_000_000)
+                })?,
+                value: 0,
+                instructions: 0,
+                cycles: 0,
+                params_hash: 0,
+                size: size_index.map_or(Some(0), |index| fields[index].parse().ok())?,
+            })
+        })();
+        results.extend(parsed);
+    }
+
+    results
+}
+
+// Collects the (algorithm, seed, batch) combinations already recorded in a
+// CSV produced by an earlier run.
+fn completed_runs(path: &str, options: &BenchmarkOptions) -> HashSet<(String, u64, usize)> {
+    read_results(path, options.seed)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|result| (result.algorithm, result.seed, result.batch))
+        .collect()
+}
+
+// Mean of `mean_ms` per algorithm, in first-seen order, so runs with several
+// batches or seeds compare as one number per algorithm.
+fn mean_by_algorithm(results: &[BenchmarkResult]) -> Vec<(String, f64)> {
+    let mut sums: Vec<(String, f64, usize)> = Vec::new();
+    for result in results {
+        match sums.iter_mut().find(|entry| entry.0 == result.algorithm) {
+            Some(entry) => {
+                entry.1 += result.mean_ms();
+                entry.2 += 1;
+            }
+            None => sums.push((result.algorithm.clone(), result.mean_ms(), 1)),
+        }
+    }
+    sums.into_iter()
+        .map(|(algorithm, sum, count)| (algorithm, sum / count as f64))
+        .collect()
+}
+
+// Three significant digits, so the summary line stays short at any scale.
+fn short_number(value: f64) -> String {
+    let decimals = if value >= 100.0 {
+        0
+    } else if value >= 10.0 {
+        1
+    } else {
+        2
+    };
+    format!("{:.*}", decimals, value)
+}
+
+// `fastest=sum_xor(12.3ms) slowest=lcs(410ms) total=0.91s`, comparing
+// per-algorithm means; the total is the summed time of every measurement.
+fn summary_line(results: &[BenchmarkResult]) -> String {
+    let means = mean_by_algorithm(results);
+    let fastest = means.iter().min_by(|a, b| a.1.total_cmp(&b.1));
+    let slowest = means.iter().max_by(|a, b| a.1.total_cmp(&b.1));
+    let total_s = results.iter().map(|result| result.total_ms).sum::<f64>() / 1000.0;
+    match (fastest, slowest) {
+        (Some(fastest), Some(slowest)) => format!(
+            "fastest={}({}ms) slowest={}({}ms) total={}s",
+            fastest.0,
+            short_number(fastest.1),
+            slowest.0,
+            short_number(slowest.1),
+            short_number(total_s)
+        ),
+        _ => "no results".to_string(),
+    }
+}
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+// Prints the per-algorithm change in mean_ms relative to the file at `path`
+// to stderr, then replaces that file with this run's results.
+fn compare_against_last(path: &str, results: &[BenchmarkResult], options: &BenchmarkOptions) {
+    match read_results(path, options.seed) {
+        Some(previous) => {
+            let color = options.format == OutputFormat::Table && io::stderr().is_terminal();
+            let previous = mean_by_algorithm(&results_since(previous, options.since, path));
+            eprintln!("Change in mean_ms against {} (negative is faster):", path);
+            for (algorithm, current_ms) in mean_by_algorithm(results) {
+                let previous_ms = match previous.iter().find(|entry| entry.0 == algorithm) {
+                    Some(entry) if entry.1 > 0.0 => entry.1,
+                    _ => {
+                        eprintln!("  {:<20} {:>12.3} ms  (new)", algorithm, current_ms);
+                        continue;
+                    }
+                };
+
+                let change = (current_ms - previous_ms) / previous_ms * 100.0;
+                let line = format!(
+                    "  {:<20} {:>12.3} ms -> {:>12.3} ms  {:+7.1}%",
+                    algorithm, previous_ms, current_ms, change
+                );
+                if color && change != 0.0 {
+                    let tint = if change < 0.0 { GREEN } else { RED };
+                    eprintln!("{}{}{}", tint, line, RESET);
+                } else {
+                    eprintln!("{}", line);
+                }
+            }
+        }
+        None => warn!("no previous results at {}; saving this run for next time.", path),
+    }
+
+    let mut file = File::create(path).unwrap_or_else(|error| panic!("Failed to open '{}': {}", path, error));
+    write_csv(&mut file, results, &active_columns(options), true, false, options.csv_quote_always)
+        .expect("Failed to write results.");
+}
+
+// Prints each algorithm's trend across a results history to stdout: the
+// least-squares slope of mean_ms over row order, how much that trend moves
+// the last row away from the first relative to the mean, and the slope's
+// t statistic. A |t| of two or more marks the trend as notable rather than
+// noise. Returns false if any algorithm slowed down by more than the
+// threshold.
+fn report_trend<W: Write>(out: &mut W, history: &[BenchmarkResult], threshold_pct: f64) -> io::Result<bool> {
+    let mut algorithms: Vec<&str> = Vec::new();
+    for result in history {
+        if !algorithms.contains(&result.algorithm.as_str()) {
+            algorithms.push(&result.algorithm);
+        }
+    }
+
+    writeln!(out, "Trend of mean_ms over the history (positive is slowing down):")?;
+    let mut regressed = Vec::new();
+    for algorithm in algorithms {
+        let samples: Vec<f64> = history
+            .iter()
+            .filter(|result| result.algorithm == algorithm)
+            .map(|result| result.mean_ms())
+            .collect();
+        if samples.len() < 3 {
+            writeln!(out, "  {:<20} {} row(s), too few for a trend", algorithm, samples.len())?;
+            continue;
+        }
+
+        let count = samples.len() as f64;
+        let mean_index = (count - 1.0) / 2.0;
+        let mean = samples.iter().sum::<f64>() / count;
+        let mut covariance = 0.0;
+        let mut variance = 0.0;
+        for (index, sample) in samples.iter().enumerate() {
+            let offset = index as f64 - mean_index;
+            covariance += offset * (sample - mean);
+            variance += offset * offset;
+        }
+        let slope = covariance / variance;
+        let residual = samples
+            .iter()
+            .enumerate()
+            .map(|(index, sample)| (sample - mean - slope * (index as f64 - mean_index)).powi(2))
+            .sum::<f64>();
+        let standard_error = (residual / (count - 2.0) / variance).sqrt();
+        let t = if standard_error > 0.0 {
+            slope / standard_error
+        } else if slope == 0.0 {
+            0.0
+        } else {
+            slope.signum() * f64::INFINITY
+        };
+        let trend_pct = if mean > 0.0 { slope * (count - 1.0) / mean * 100.0 } else { 0.0 };
+
+        let notable = if t.abs() >= 2.0 { "  notable" } else { "" };
+        let flag = if trend_pct > threshold_pct {
+            regressed.push(algorithm);
+            "  regressed"
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "  {:<20} {:>+12.6} ms/run  {:+7.1}% over {} rows  t={:+.2}{}{}",
+            algorithm, slope, trend_pct, samples.len(), t, notable, flag
+        )?;
+    }
+    if !regressed.is_empty() {
+        error!(
+            "{} slowed down by more than {}% over the history.",
+            regressed.join(", "),
+            threshold_pct
+        );
+    }
+    Ok(regressed.is_empty())
+}
+
+// Prints each algorithm's per-iteration trend to stderr: the fitted slope
+// and how much slower (or faster) that trend makes the last iteration than
+// the first, relative to the mean. Algorithms measured more than once
+// report the average over their measurements. A sustained positive drift
+// beyond the threshold usually means thermal throttling.
+fn report_drift(results: &[BenchmarkResult], threshold_pct: f64) {
+    let mut algorithms: Vec<&str> = Vec::new();
+    for result in results {
+        if !algorithms.contains(&result.algorithm.as_str()) {
+            algorithms.push(&result.algorithm);
+        }
+    }
+
+    eprintln!("Per-iteration drift (positive is slowing down):");
+    let mut drifting = Vec::new();
+    for algorithm in algorithms {
+        let measured: Vec<&BenchmarkResult> =
+            results.iter().filter(|result| result.algorithm == algorithm).collect();
+        let count = measured.len() as f64;
+        let slope = measured.iter().map(|result| result.drift_ms_per_iteration()).sum::<f64>() / count;
+        let drift_pct = measured
+            .iter()
+            .map(|result| {
+                let span = result.samples_ns.len().saturating_sub(1) as f64;
+                let mean = result.mean_ms();
+                if mean > 0.0 {
+                    result.drift_ms_per_iteration() * span / mean * 100.0
+                } else {
+                    0.0
+                }
+            })
+            .sum::<f64>()
+            / count;
+        let flag = if drift_pct > threshold_pct {
+            drifting.push(algorithm);
+            "  drifting"
+        } else {
+            ""
+        };
+        eprintln!(
+            "  {:<20} {:>+12.6} ms/iteration  {:+7.1}% over the run{}",
+            algorithm, slope, drift_pct, flag
+        );
+    }
+    if !drifting.is_empty() {
+        strict_warn!(
+            "{} drifted by more than {}% over the run.",
+            drifting.join(", "),
+            threshold_pct
+        );
+    }
+}
+
+// --ramp-report runs this many iterations unless --iterations says otherwise.
+const RAMP_ITERATIONS: usize = 50;
+const RAMP_SETTLED_PCT: f64 = 5.0;
+
+// Normalizes every measurement's samples by its steady duration, the median
+// of its last quarter, and prints the median ratio at each iteration index
+// across all measurements, with a bar per row to show the shape.
+fn report_ramp(results: &[BenchmarkResult]) {
+    let mut by_index: Vec<Vec<f64>> = Vec::new();
+    for result in results {
+        let samples = result.samples_ms();
+        let mut tail: Vec<f64> = samples[samples.len() - samples.len().div_ceil(4)..].to_vec();
+        tail.sort_by(|a, b| a.total_cmp(b));
+        let steady = tail[tail.len() / 2];
+        if steady <= 0.0 {
+            continue;
+        }
+        for (index, sample) in samples.iter().enumerate() {
+            if index == by_index.len() {
+                by_index.push(Vec::new());
+            }
+            by_index[index].push(sample / steady);
+        }
+    }
+
+    let ratios: Vec<f64> = by_index
+        .iter_mut()
+        .map(|ratios| {
+            ratios.sort_by(|a, b| a.total_cmp(b));
+            ratios[ratios.len() / 2]
+        })
+        .collect();
+    eprintln!("Ramp to steady state (duration relative to the median of the last quarter, median over algorithms):");
+    for (index, ratio) in ratios.iter().enumerate() {
+        let bar = "#".repeat(((ratio * 40.0).round() as usize).min(80));
+        eprintln!("  {:>5} {:>7.3}  {}", index + 1, ratio, bar);
+    }
+    let settled = ratios
+        .iter()
+        .rposition(|ratio| (ratio - 1.0).abs() * 100.0 > RAMP_SETTLED_PCT)
+        .map_or(1, |index| index + 2);
+    if settled <= ratios.len() {
+        eprintln!("Within {}% of steady from iteration {} on.", RAMP_SETTLED_PCT, settled);
+    } else {
+        eprintln!("Not within {}% of steady by the last iteration; try more iterations.", RAMP_SETTLED_PCT);
+    }
+}
+
+// Each algorithm's share of the summed total_ms of the run, largest first,
+// with the running total so the few kernels that dominate stand out.
+fn report_hot(results: &[BenchmarkResult]) {
+    let mut totals: Vec<(&str, f64)> = Vec::new();
+    for result in results {
+        match totals.iter_mut().find(|entry| entry.0 == result.algorithm) {
+            Some(entry) => entry.1 += result.total_ms,
+            None => totals.push((&result.algorithm, result.total_ms)),
+        }
+    }
+    totals.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let suite_ms: f64 = totals.iter().map(|entry| entry.1).sum();
+
+    eprintln!("Share of suite time ({:.3} ms in total):", suite_ms);
+    let mut cumulative = 0.0;
+    for (algorithm, total_ms) in totals {
+        let share = if suite_ms > 0.0 { total_ms / suite_ms * 100.0 } else { 0.0 };
+        cumulative += share;
+        eprintln!(
+            "  {:<20} {:>12.3} ms  {:>5.1}%  {:>5.1}% cumulative",
+            algorithm, total_ms, share, cumulative
+        );
+    }
+}
+
+// The sidecar file of raw per-iteration samples. It follows --append like
+// the main output and carries `seed`/`batch` under the same conditions.
+fn open_samples(path: &str, options: &BenchmarkOptions) -> File {
+    let needs_header = !options.append || read_header(path).is_none();
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(options.append)
+        .truncate(!options.append)
+        .open(path)
+        .unwrap_or_else(|error| panic!("Failed to open '{}': {}", path, error));
+    if needs_header {
+        let header = if active_columns(options).contains(&Column::Seed) {
+            "algorithm,seed,batch,iteration_index,duration_ns"
+        } else {
+            "algorithm,iteration_index,duration_ns"
+        };
+        writeln!(file, "{}", header).expect("Failed to write samples.");
+    }
+    file
+}
+
+fn write_samples<W: Write>(out: &mut W, result: &BenchmarkResult, with_seed: bool) -> io::Result<()> {
+    for (index, duration_ns) in result.samples_ns.iter().enumerate() {
+        if with_seed {
+            writeln!(
+                out,
+                "{},{},{},{},{}",
+                result.algorithm, result.seed, result.batch, index, duration_ns
+            )?;
+        } else {
+            writeln!(out, "{},{},{}", result.algorithm, index, duration_ns)?;
+        }
+    }
+    out.flush()
+}
+
+// Prints each algorithm's speedup over the baseline results to stderr under
+// `title` (above 1 is faster) and returns the speedups of the algorithms
+// present in both sets.
+fn report_speedups(title: &str, baseline: &[BenchmarkResult], results: &[BenchmarkResult]) -> Vec<f64> {
+    let baseline = mean_by_algorithm(baseline);
+    let mut speedups = Vec::new();
+    eprintln!("{} (above 1 is faster):", title);
+    for (algorithm, current_ms) in mean_by_algorithm(results) {
+        match baseline.iter().find(|entry| entry.0 == algorithm) {
+            Some(entry) if entry.1 > 0.0 && current_ms > 0.0 => {
+                let speedup = entry.1 / current_ms;
+                eprintln!(
+                    "  {:<20} {:>12.3} ms -> {:>12.3} ms  {:>8.3}x",
+                    algorithm, entry.1, current_ms, speedup
+                );
+                speedups.push(speedup);
+            }
+            _ => eprintln!("  {:<20} {:>12.3} ms  (not in baseline)", algorithm, current_ms),
+        }
+    }
+    speedups
+}
+
+// Zero and negative values have no logarithm and are left out.
+fn geometric_mean(values: &[f64]) -> Option<f64> {
+    let logs: Vec<f64> = values.iter().filter(|value| **value > 0.0).map(|value| value.ln()).collect();
+    if logs.is_empty() {
+        None
+    } else {
+        Some((logs.iter().sum::<f64>() / logs.len() as f64).exp())
+    }
+}
+
+// Reference values are stored as `algorithm,seed,value` rows, one per
+// kernel call result. They are only valid for the kernel parameters and
+// --rng they were written with.
+fn write_reference(path: &str, results: &[BenchmarkResult]) {
+    let mut file = File::create(path).unwrap_or_else(|error| panic!("Failed to open '{}': {}", path, error));
+    writeln!(file, "algorithm,seed,value").expect("Failed to write reference values.");
+    for result in results {
+        writeln!(file, "{},{},{}", result.algorithm, result.seed, result.value)
+            .expect("Failed to write reference values.");
+    }
+}
+
+fn read_reference(path: &str) -> Option<Vec<(String, u64, u64)>> {
+    let file = File::open(path).ok()?;
+    parse_reference(BufReader::new(file))
+}
+
+fn parse_reference<R: BufRead>(reader: R) -> Option<Vec<(String, u64, u64)>> {
+    let mut references = Vec::new();
+    for line in reader.lines().skip(1) {
+        let line = line.ok()?;
+        let fields: Vec<&str> = line.trim_end().split(',').collect();
+        if let [algorithm, seed, value] = fields[..] {
+            if let (Ok(seed), Ok(value)) = (seed.parse(), value.parse()) {
+                references.push((algorithm.to_string(), seed, value));
+            }
+        }
+    }
+    Some(references)
+}
+
+// The distance between a value and its reference, read as signed 64-bit
+// integers so quantized negatives and f64 bit patterns compare sensibly.
+fn value_distance(value: u64, reference: u64) -> u64 {
+    (value as i64).wrapping_sub(reference as i64).unsigned_abs()
+}
+
+// Checks one result against its reference value from `path`. A kernel
+// without a reference is warned about, not failed.
+fn verify_result(
+    path: &str,
+    references: &[(String, u64, u64)],
+    result: &BenchmarkResult,
+    tolerance_override: Option<u64>,
+) -> bool {
+    let reference = references
+        .iter()
+        .find(|entry| entry.0 == result.algorithm && entry.1 == result.seed);
+    let reference = match reference {
+        Some(entry) => entry.2,
+        None => {
+            strict_warn!("no reference value for {} seed {} in {}.", result.algorithm, result.seed, path);
+            return true;
+        }
+    };
+    let tolerance = KERNELS
+        .iter()
+        .find(|kernel| kernel.name == result.algorithm)
+        .map_or(0, |kernel| match tolerance_override {
+            Some(tolerance) if kernel.checksum_tolerance > 0 => tolerance,
+            _ => kernel.checksum_tolerance,
+        });
+    let distance = value_distance(result.value, reference);
+    if distance > tolerance {
+        error!(
+            "{} seed {}: value {} does not match reference {} (distance {}, tolerance {}).",
+            result.algorithm, result.seed, result.value, reference, distance, tolerance
+        );
+        return false;
+    }
+    if distance > 0 {
+        info!(
+            "{} seed {}: value within tolerance of its reference (distance {}).",
+            result.algorithm, result.seed, distance
+        );
+    }
+    true
+}
+
+// Checks every result, so all mismatches are reported, and tells whether
+// all of them matched.
+fn verify_results(
+    path: &str,
+    references: &[(String, u64, u64)],
+    results: &[BenchmarkResult],
+    tolerance_override: Option<u64>,
+) -> bool {
+    let mut all_match = true;
+    for result in results {
+        all_match &= verify_result(path, references, result, tolerance_override);
+    }
+    all_match
+}
+
+// What every kernel returns at its default size for DEFAULT_SEED with the
+// LCG, baked in at compile time. It has to be regenerated, as described in
+// the README, whenever a kernel's result changes on purpose or a kernel is
+// added; --self-verify then fails until it is.
+const EMBEDDED_REFERENCE: &str = include_str!("bench_rust_reference.csv");
+
+// Runs every kernel once with the default options and stops the process if
+// any result differs from EMBEDDED_REFERENCE beyond its registry tolerance,
+// so a build with a semantic change cannot quietly become a reference.
+fn self_verify() {
+    let references = parse_reference(EMBEDDED_REFERENCE.as_bytes()).unwrap_or_default();
+    let defaults = default_options();
+    let rng = INPUT_RNG.swap(RngKind::Lcg as usize, Ordering::Relaxed);
+    let mut failed = 0;
+    for kernel in KERNELS {
+        let value = (kernel.run)(&defaults, DEFAULT_SEED);
+        let reference = references
+            .iter()
+            .find(|entry| entry.0 == kernel.name && entry.1 == DEFAULT_SEED);
+        match reference {
+            Some(entry) if value_distance(value, entry.2) <= kernel.checksum_tolerance => {}
+            Some(entry) => {
+                error!("self-verify: {} returned {}, the embedded reference is {}.", kernel.name, value, entry.2);
+                failed += 1;
+            }
+            None => {
+                error!("self-verify: {} has no embedded reference value.", kernel.name);
+                failed += 1;
+            }
+        }
+    }
+    INPUT_RNG.store(rng, Ordering::Relaxed);
+
+    if failed > 0 {
+        error!("self-verify failed for {} of {} kernels.", failed, KERNELS.len());
+        std::process::exit(1);
+    }
+    info!("self-verify: all {} kernels match the embedded reference.", KERNELS.len());
+}
+
+// Kernel `K` called with the default options and DEFAULT_SEED as constants
+// the optimizer can see through, for --fairness-check. Indices past the
+// end of KERNELS return 0; they only exist to fill the last table block.
+fn literal_kernel<const K: usize>() -> u64 {
+    let options = default_options();
+    match KERNELS.get(K) {
+        Some(kernel) => (kernel.run)(&options, DEFAULT_SEED),
+        None => 0,
+    }
+}
+
+macro_rules! literal_kernel_block {
+    ($block:literal) => {
+        literal_kernel_block!($block; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15)
+    };
+    ($block:literal; $($offset:literal)*) => {
+        [$(literal_kernel::<{ $block * 16 + $offset }> as fn() -> u64),*]
+    };
+}
+
+// literal_kernel for every index below LITERAL_KERNEL_LIMIT, in blocks of
+// 16. Entry `i` calls KERNELS[i] by construction, so new kernels need no
+// change here until the registry outgrows the table.
+const LITERAL_KERNEL_LIMIT: usize = 128;
+const LITERAL_KERNELS: [[fn() -> u64; 16]; LITERAL_KERNEL_LIMIT / 16] = [
+    literal_kernel_block!(0),
+    literal_kernel_block!(1),
+    literal_kernel_block!(2),
+    literal_kernel_block!(3),
+    literal_kernel_block!(4),
+    literal_kernel_block!(5),
+    literal_kernel_block!(6),
+    literal_kernel_block!(7),
+];
+
+const _: () = assert!(KERNELS.len() <= LITERAL_KERNEL_LIMIT, "raise LITERAL_KERNEL_LIMIT");
+
+// A literal-size call this much faster than the black_box one is reported
+// as likely constant-folded. Kernels faster than the floor even with
+// black_box, such as noop, are too quick to compare.
+const FOLD_RATIO: f64 = 0.25;
+const FOLD_FLOOR_MS: f64 = 0.01;
+
+// Best-of-`calls` wall time of `call`, in milliseconds.
+fn best_call_ms(calls: usize, call: impl Fn() -> u64) -> f64 {
+    (0..calls.max(1))
+        .map(|_| {
+            let started = Instant::now();
+            std::hint::black_box(call());
+            started.elapsed().as_secs_f64() * 1e3
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+// Times every kernel at its default size twice, once with the options and
+// seed hidden behind black_box and once through LITERAL_KERNELS with them
+// as constants, and prints both to stderr. Ports whose compilers precompute
+// a kernel from constant inputs look the same way: much faster with literal
+// sizes. Runs before the measurements and leaves them untouched.
+fn fairness_check(calls: usize) {
+    let defaults = default_options();
+    eprintln!("Constant-folding check at default sizes (best of {} calls):", calls.max(1));
+    let mut folded = Vec::new();
+    for (index, kernel) in KERNELS.iter().enumerate() {
+        let literal = LITERAL_KERNELS[index / 16][index % 16];
+        let runtime_ms = best_call_ms(calls, || {
+            (kernel.run)(std::hint::black_box(&defaults), std::hint::black_box(DEFAULT_SEED))
+        });
+        let literal_ms = best_call_ms(calls, literal);
+        let note = if runtime_ms < FOLD_FLOOR_MS {
+            "  too fast to compare"
+        } else if literal_ms < runtime_ms * FOLD_RATIO {
+            folded.push(kernel.name);
+            "  likely folded"
+        } else {
+            ""
+        };
+        eprintln!(
+            "  {:<20} {:>12.3} ms black_box {:>12.3} ms literal{}",
+            kernel.name, runtime_ms, literal_ms, note
+        );
+    }
+    if !folded.is_empty() {
+        strict_warn!(
+            "{} ran more than {}x faster with literal sizes; the optimizer may be precomputing them.",
+            folded.join(", "),
+            1.0 / FOLD_RATIO
+        );
+    }
+}
+
+// Runs `command` through the shell and parses the results CSV it prints.
+fn run_compare_command(command: &str, default_seed: u64) -> Vec<BenchmarkResult> {
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let output = std::process::Command::new(shell)
+        .args([flag, command])
+        .stderr(std::process::Stdio::inherit())
+        .output();
+    match output {
+        Ok(output) if output.status.success() => parse_results(&output.stdout[..], command, default_seed),
+        Ok(output) => {
+            error!("--compare-cmd '{}' failed with {}.", command, output.status);
+            std::process::exit(1);
+        }
+        Err(error) => {
+            error!("cannot run --compare-cmd '{}': {}", command, error);
+            std::process::exit(1);
+        }
+    }
+}
+
+// Describes every registered kernel at the default options, so runners for
+// other languages can size their kernels the same way without keeping their
+// own copy of the list.
+fn manifest_json() -> JsonObject {
+    let defaults = default_options();
+    let kernels: Vec<JsonObject> = KERNELS
+        .iter()
+        .map(|kernel| {
+            let mut json = JsonObject::new();
+            json.string("name", kernel.name);
+            json.string("size_flag", kernel.size_flag);
+            json.number("default_size", (kernel.size)(&defaults));
+            json.string("work_unit", kernel.work_unit);
+            json.boolean("fuzzy_checksum", kernel.checksum_tolerance > 0);
+            json.number("checksum_tolerance", kernel.checksum_tolerance);
+            json
+        })
+        .collect();
+
+    let mut json = JsonObject::new();
+    json.number("default_iterations", defaults.iterations);
+    json.number("default_seed", defaults.seed);
+    json.object_array("kernels", &kernels);
+    json
+}
+
+struct PlannedRun {
+    kernel: &'static Kernel,
+    seed: u64,
+    batch: usize,
+    // Zero-based execution slot within the batch.
+    position: usize,
+    // The size --size-range drew for this run, if it resizes this kernel.
+    size: Option<u64>,
+}
+
+// The --size-range size for one (seed, batch): `low + mix(seed ^ (batch *
+// 0x9e3779b97f4a7c15)) % (high - low + 1)` with the splitmix64 finalizer
+// as `mix`, so a batch gets the same size whenever it is run again.
+fn sampled_size(low: u64, high: u64, seed: u64, batch: usize) -> u64 {
+    let z = seed ^ (batch as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    low + splitmix64_mix(z) % (high - low).saturating_add(1)
+}
+
+// Lays out every (seed, batch, kernel) measurement in execution order,
+// leaving out combinations that --resume found already recorded. With
+// --shuffle-order each batch runs its kernels in a Fisher-Yates permutation
+// drawn from one LCG seeded with --shuffle-seed.
+fn build_plan(
+    options: &BenchmarkOptions,
+    completed: &HashSet<(String, u64, usize)>,
+) -> Vec<PlannedRun> {
+    let mut shuffle_rng = InputRng::with_kind(RngKind::Lcg, options.shuffle_seed);
+    let mut plan = Vec::new();
+    for sweep_index in 0..options.seed_sweep {
+        let seed = options.seed.wrapping_add(sweep_index as u64);
+        for batch in 1..=options.repeat {
+            let mut order: Vec<&'static Kernel> = KERNELS.iter().collect();
+            if options.shuffle_order {
+                for index in (1..order.len()).rev() {
+                    let swap_with = (shuffle_rng.next() % (index as u64 + 1)) as usize;
+                    order.swap(index, swap_with);
+                }
+                let names: Vec<&str> = order.iter().map(|kernel| kernel.name).collect();
+                info!("seed {} batch {} order: {}", seed, batch, names.join(","));
+            }
+
+            for (position, kernel) in order.into_iter().enumerate() {
+                if !completed.contains(&(kernel.name.to_string(), seed, batch)) {
+                    let resized = options.size_kernel.as_deref() == Some(kernel.name);
+                    plan.push(PlannedRun {
+                        kernel,
+                        seed,
+                        batch,
+                        position,
+                        size: options
+                            .size_range
+                            .filter(|_| resized)
+                            .map(|(low, high)| sampled_size(low, high, seed, batch)),
+                    });
+                }
+            }
+        }
+    }
+    plan
+}
+
+// The running mean of one algorithm and seed's mean_ms over its batches so
+// far, for --repeat-until-stable. It converges once adding a batch moves the
+// running mean by less than the threshold.
+struct Convergence {
+    algorithm: &'static str,
+    seed: u64,
+    batches: usize,
+    sum_ms: f64,
+    change_pct: f64,
+    converged: bool,
+}
+
+impl Convergence {
+    fn add(&mut self, mean_ms: f64, threshold_pct: f64) {
+        let previous = self.running_mean_ms();
+        self.batches += 1;
+        self.sum_ms += mean_ms;
+        if self.batches >= 2 {
+            let current = self.running_mean_ms();
+            self.change_pct = if previous > 0.0 {
+                (current - previous).abs() / previous * 100.0
+            } else {
+                0.0
+            };
+            self.converged = self.change_pct < threshold_pct;
+        }
+    }
+
+    fn running_mean_ms(&self) -> f64 {
+        if self.batches == 0 {
+            0.0
+        } else {
+            self.sum_ms / self.batches as f64
+        }
+    }
+}
+
+fn report_convergence(convergence: &[Convergence], options: &BenchmarkOptions) {
+    eprintln!(
+        "Repeat until stable (running mean change below {}%, at most {} batches):",
+        options.stable_threshold, options.max_batches
+    );
+    for entry in convergence {
+        let name = if options.seed_sweep > 1 {
+            format!("{} seed {}", entry.algorithm, entry.seed)
+        } else {
+            entry.algorithm.to_string()
+        };
+        let status = if entry.converged {
+            "converged".to_string()
+        } else {
+            format!("hit the cap, last change {:.2}%", entry.change_pct)
+        };
+        eprintln!(
+            "  {:<20} {:>3} batches  mean {:>12.6} ms  {}",
+            name,
+            entry.batches,
+            entry.running_mean_ms(),
+            status
+        );
+    }
+}
+
+fn measure_kernel(
+    run: &PlannedRun,
+    options: &BenchmarkOptions,
+    perf: Option<&PerfCounters>,
+) -> BenchmarkResult {
+    let resized;
+    let options = match run.size {
+        Some(size) => {
+            let mut copy = options.clone();
+            (run.kernel.set_size)(&mut copy, size);
+            resized = copy;
+            &resized
+        }
+        None => options,
+    };
+    let collect_samples = options.collects_samples();
+    let mut samples_ns = Vec::with_capacity(if collect_samples { options.iterations } else { 0 });
+    let started_ns = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+    if let Some(perf) = perf {
+        perf.start();
+    }
+    let started = Instant::now();
+    let mut checksum = 0u64;
+    let mut value = 0u64;
+    for i in 0..options.iterations {
+        if collect_samples {
+            let iteration_started = Instant::now();
+            value = (run.kernel.run)(options, run.seed);
+            checksum = mix_checksum(checksum, value, i as u64);
+            samples_ns.push(iteration_started.elapsed().as_nanos() as u64);
+        } else {
+            value = (run.kernel.run)(options, run.seed);
+            checksum = mix_checksum(checksum, value, i as u64);
+        }
+    }
+    let total_ms = started.elapsed().as_secs_f64() * 1000.0;
+    let (instructions, cycles) = perf.map_or((0, 0), |perf| perf.stop());
+
+    BenchmarkResult {
+        algorithm: run.kernel.name.to_string(),
+        iterations: options.iterations,
+        total_ms,
+        checksum,
+        seed: run.seed,
+        batch: run.batch,
+        position: run.position,
+        samples_ns,
+        started_ns,
+        value,
+        instructions,
+        cycles,
+        params_hash: params_hash(run.kernel, options, run.seed),
+        size: (run.kernel.size)(options),
+    }
+}
+
+// Identifies the parameters behind one row: the 64-bit FNV-1a hash of
+// `algorithm=<name>;size=<size>;seed=<seed>;iterations=<n>;rng=<rng>`,
+// with the kernel's size option as resolved and numbers in decimal. Rows
+// from runs with the same parameters share it, whatever build made them.
+fn params_hash(kernel: &Kernel, options: &BenchmarkOptions, seed: u64) -> u64 {
+    let key = format!(
+        "algorithm={};size={};seed={};iterations={};rng={}",
+        kernel.name,
+        (kernel.size)(options),
+        seed,
+        options.iterations,
+        options.rng.name()
+    );
+    fnv1a(key.as_bytes())
+}
+
+const SELF_AUDIT_FLOOR_MS: f64 = 0.05;
+
+// Checks that the per-iteration samples add up to the separately timed
+// total. A gap beyond the tolerance means time is leaking in between
+// iterations, e.g. measurement overhead introduced by a timing refactor.
+fn self_audit(result: &BenchmarkResult, tolerance_pct: f64) {
+    let sampled_ms: f64 = result.samples_ns.iter().map(|ns| *ns as f64 / 1e6).sum();
+    let gap_ms = (result.total_ms - sampled_ms).abs();
+    if gap_ms > SELF_AUDIT_FLOOR_MS && gap_ms > result.total_ms * tolerance_pct / 100.0 {
+        strict_warn!(
+            "self-audit: {} total_ms {:.3} differs from the sum of its iterations {:.3} by {:.3} ms.",
+            result.algorithm, result.total_ms, sampled_ms, gap_ms
+        );
+    }
+}
+
+// A single self-overwriting status line on stderr. It is only drawn when
+// stderr is a terminal, so redirected logs never see the escape sequences.
+struct Progress {
+    enabled: bool,
+    total: usize,
+    done: usize,
+    repeat: usize,
+    seed_sweep: usize,
+}
+
+impl Progress {
+    fn new(total: usize, options: &BenchmarkOptions) -> Progress {
+        Progress {
+            enabled: io::stderr().is_terminal(),
+            total,
+            done: 0,
+            repeat: options.repeat,
+            seed_sweep: options.seed_sweep,
+        }
+    }
+
+    fn advance(&mut self, algorithm: &str, seed: u64, batch: usize) {
+        self.done += 1;
+        if !self.enabled {
+            return;
+        }
+
+        let mut line = format!("[{}/{}] {}", self.done, self.total, algorithm);
+        if self.repeat > 1 {
+            line.push_str(&format!(" batch {}/{}", batch, self.repeat));
+        }
+        if self.seed_sweep > 1 {
+            line.push_str(&format!(" seed {}", seed));
+        }
+        eprint!("\r{}\x1b[K", line);
+    }
+
+    fn finish(&self) {
+        if self.enabled && self.done > 0 {
+            eprint!("\r\x1b[K");
+        }
+    }
+}
+
+struct Kernel {
+    name: &'static str,
+    // The option that scales the kernel, its value under the given options,
+    // and what one unit of that size is; published by --manifest.
+    size_flag: &'static str,
+    size: fn(&BenchmarkOptions) -> u64,
+    // Sets that option, for the sizes --size-range draws.
+    set_size: fn(&mut BenchmarkOptions, u64),
+    work_unit: &'static str,
+    run: fn(&BenchmarkOptions, u64) -> u64,
+    // Inner-loop steps per iteration for kernels whose cost grows faster than
+    // their size parameter, used to catch accidentally huge runs up front.
+    estimated_ops: Option<fn(&BenchmarkOptions) -> u64>,
+    // How far the kernel's value may be from its reference under --verify.
+    // Zero (exact) for integer kernels; floating-point kernels whose value is
+    // a quantized number or raw f64 bits allow a few units of rounding
+    // difference between languages. Kernels that fold many quantized values
+    // through a hash (e.g. euler_step) cannot be compared by distance and
+    // stay exact.
+    checksum_tolerance: u64,
+}
+
+const KERNELS: &[Kernel] = &[
+    Kernel {
+        name: "sum_xor",
+        size_flag: "--sum-n",
+        size: |options| options.sum_n,
+        set_size: |options, size| options.sum_n = size,
+        work_unit: "loop iteration",
+        run: |options, _| run_sum_xor(options.sum_n),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "prime_trial",
+        size_flag: "--prime-n",
+        size: |options| options.prime_n as u64,
+        set_size: |options, size| options.prime_n = size as _,
+        work_unit: "candidate tested by trial division",
+        run: |options, _| run_prime_trial(options.prime_n),
+        estimated_ops: Some(|options| {
+            let n = options.prime_n as u64;
+            n.saturating_mul((n as f64).sqrt() as u64)
+        }),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "affine_grid",
+        size_flag: "--matrix-n",
+        size: |options| options.matrix_n as u64,
+        set_size: |options, size| options.matrix_n = size as _,
+        work_unit: "grid side; n^3 inner steps",
+        run: |options, _| run_affine_grid(options.matrix_n),
+        estimated_ops: Some(|options| (options.matrix_n as u64).saturating_pow(3)),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "branch_mix",
+        size_flag: "--sum-n",
+        size: |options| options.sum_n,
+        set_size: |options, size| options.sum_n = size,
+        work_unit: "loop iteration",
+        run: |options, _| run_branch_mix(options.sum_n),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "gcd_fold",
+        size_flag: "--prime-n",
+        size: |options| options.prime_n as u64,
+        set_size: |options, size| options.prime_n = size as _,
+        work_unit: "gcd reduction",
+        run: |options, _| run_gcd_fold(options.prime_n),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "lcg_stream",
+        size_flag: "--sum-n",
+        size: |options| options.sum_n,
+        set_size: |options, size| options.sum_n = size,
+        work_unit: "generator step",
+        run: |options, seed| run_lcg_stream(options.sum_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "sort_pairs",
+        size_flag: "--pairs-n",
+        size: |options| options.pairs_n as u64,
+        set_size: |options, size| options.pairs_n = size as _,
+        work_unit: "record sorted",
+        run: |options, seed| run_sort_pairs(options.pairs_n, seed),
+        estimated_ops: Some(|options| {
+            let n = options.pairs_n as u64;
+            n.saturating_mul(64 - n.leading_zeros() as u64)
+        }),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "div_stream",
+        size_flag: "--div-n",
+        size: |options| options.div_n,
+        set_size: |options, size| options.div_n = size,
+        work_unit: "division",
+        run: |options, _| run_div_stream(options.div_n),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "dot_product",
+        size_flag: "--dot-n",
+        size: |options| options.dot_n as u64,
+        set_size: |options, size| options.dot_n = size as _,
+        work_unit: "vector element",
+        run: |options, seed| run_dot_product(options.dot_n, options.dot_order, seed),
+        estimated_ops: None,
+        checksum_tolerance: 16,
+    },
+    Kernel {
+        name: "atomic_contend",
+        size_flag: "--atomic-n",
+        size: |options| options.atomic_n,
+        set_size: |options, size| options.atomic_n = size,
+        work_unit: "atomic increment per thread",
+        run: |options, _| run_atomic_contend(options.atomic_n, options.threads),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "string_map",
+        size_flag: "--strmap-n",
+        size: |options| options.strmap_n as u64,
+        set_size: |options, size| options.strmap_n = size as _,
+        work_unit: "key inserted and looked up",
+        run: |options, seed| run_string_map(options.strmap_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "rle_roundtrip",
+        size_flag: "--rle-n",
+        size: |options| options.rle_n as u64,
+        set_size: |options, size| options.rle_n = size as _,
+        work_unit: "input byte",
+        run: |options, seed| run_rle_roundtrip(options.rle_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "parse_ints",
+        size_flag: "--parse-n",
+        size: |options| options.parse_n as u64,
+        set_size: |options, size| options.parse_n = size as _,
+        work_unit: "integer formatted and parsed",
+        run: |options, seed| run_parse_ints(options.parse_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "heap_ops",
+        size_flag: "--heap-n",
+        size: |options| options.heap_n as u64,
+        set_size: |options, size| options.heap_n = size as _,
+        work_unit: "element pushed and popped",
+        run: |options, seed| run_heap_ops(options.heap_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "memcopy",
+        size_flag: "--copy-kb",
+        size: |options| options.copy_kb as u64,
+        set_size: |options, size| options.copy_kb = size as _,
+        work_unit: "KiB of buffer, copied --copy-reps times",
+        run: |options, _| run_memcopy(options.copy_kb, options.copy_reps),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "newton_sqrt",
+        size_flag: "--newton-n",
+        size: |options| options.newton_n as u64,
+        set_size: |options, size| options.newton_n = size as _,
+        work_unit: "square root",
+        run: |options, seed| run_newton_sqrt(options.newton_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "bitset_ops",
+        size_flag: "--bitset-n",
+        size: |options| options.bitset_n as u64,
+        set_size: |options, size| options.bitset_n = size as _,
+        work_unit: "bit operation",
+        run: |options, seed| run_bitset_ops(options.bitset_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "vm_mix",
+        size_flag: "--vm-mix-n",
+        size: |options| options.vm_mix_n as u64,
+        set_size: |options, size| options.vm_mix_n = size as _,
+        work_unit: "interpreted instruction",
+        run: |options, seed| run_vm_mix(options.vm_mix_n, options.vm_arith_pct, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "bfs",
+        size_flag: "--bfs-nodes",
+        size: |options| options.bfs_nodes as u64,
+        set_size: |options, size| options.bfs_nodes = size as _,
+        work_unit: "graph node",
+        run: |options, seed| run_bfs(options.bfs_nodes, options.bfs_degree, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "bst",
+        size_flag: "--bst-n",
+        size: |options| options.bst_n as u64,
+        set_size: |options, size| options.bst_n = size as _,
+        work_unit: "key inserted",
+        run: |options, seed| run_bst(options.bst_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "saturate_fold",
+        size_flag: "--sat-n",
+        size: |options| options.sat_n as u64,
+        set_size: |options, size| options.sat_n = size as _,
+        work_unit: "saturating step",
+        run: |options, seed| run_saturate_fold(options.sat_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "layout",
+        size_flag: "--layout-n",
+        size: |options| options.layout_n as u64,
+        set_size: |options, size| options.layout_n = size as _,
+        work_unit: "particle",
+        run: |options, seed| run_layout(options.layout_n, options.layout, seed),
+        estimated_ops: None,
+        checksum_tolerance: 16,
+    },
+    Kernel {
+        name: "prefix_sum",
+        size_flag: "--scan-n",
+        size: |options| options.scan_n as u64,
+        set_size: |options, size| options.scan_n = size as _,
+        work_unit: "scanned element",
+        run: |options, seed| run_prefix_sum(options.scan_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "base64_roundtrip",
+        size_flag: "--b64-n",
+        size: |options| options.b64_n as u64,
+        set_size: |options, size| options.b64_n = size as _,
+        work_unit: "input byte",
+        run: |options, seed| run_base64_roundtrip(options.b64_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "error_flow",
+        size_flag: "--err-n",
+        size: |options| options.err_n as u64,
+        set_size: |options, size| options.err_n = size as _,
+        work_unit: "fallible call",
+        run: |options, seed| run_error_flow(options.err_n, options.err_rate, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "euler_step",
+        size_flag: "--bodies",
+        size: |options| options.bodies as u64,
+        set_size: |options, size| options.bodies = size as _,
+        work_unit: "body, integrated for --steps timesteps",
+        run: |options, seed| run_euler_step(options.bodies, options.steps, seed),
+        estimated_ops: Some(|options| (options.bodies as u64).saturating_mul(options.steps as u64)),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "closure_apply",
+        size_flag: "--closure-n",
+        size: |options| options.closure_n as u64,
+        set_size: |options, size| options.closure_n = size as _,
+        work_unit: "closure call",
+        run: |options, seed| run_closure_apply(options.closure_n, options.closure, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "flood_fill",
+        size_flag: "--flood-n",
+        size: |options| options.flood_n as u64,
+        set_size: |options, size| options.flood_n = size as _,
+        work_unit: "grid side; n^2 cells",
+        run: |options, seed| run_flood_fill(options.flood_n, seed),
+        estimated_ops: Some(|options| (options.flood_n as u64).saturating_pow(2)),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "iterator_pipeline",
+        size_flag: "--iter-n",
+        size: |options| options.iter_n,
+        set_size: |options, size| options.iter_n = size,
+        work_unit: "range element",
+        run: |options, seed| run_iterator_pipeline(options.iter_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "lcs",
+        size_flag: "--lcs-n",
+        size: |options| options.lcs_n as u64,
+        set_size: |options, size| options.lcs_n = size as _,
+        work_unit: "sequence length; n^2 table cells",
+        run: |options, seed| run_lcs(options.lcs_n, seed),
+        estimated_ops: Some(|options| (options.lcs_n as u64).saturating_pow(2)),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "tail_sum",
+        size_flag: "--tail-n",
+        size: |options| options.tail_n,
+        set_size: |options, size| options.tail_n = size,
+        work_unit: "tail call",
+        run: |options, seed| run_tail_sum(options.tail_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "gap_shift",
+        size_flag: "--gap-n",
+        size: |options| options.gap_n as u64,
+        set_size: |options, size| options.gap_n = size as _,
+        work_unit: "insertion; about n^2/4 shifted elements",
+        run: |options, seed| run_gap_shift(options.gap_n, seed),
+        estimated_ops: Some(|options| (options.gap_n as u64).saturating_pow(2) / 4),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "tokenize_numbers",
+        size_flag: "--tok-n",
+        size: |options| options.tok_n as u64,
+        set_size: |options, size| options.tok_n = size as _,
+        work_unit: "number token",
+        run: |options, seed| run_tokenize_numbers(options.tok_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "bignum_add",
+        size_flag: "--bignum-digits",
+        size: |options| options.bignum_digits as u64,
+        set_size: |options, size| options.bignum_digits = size as _,
+        work_unit: "32-bit limb, added --bignum-reps times",
+        run: |options, seed| run_bignum_add(options.bignum_digits, options.bignum_reps, seed),
+        estimated_ops: Some(|options| (options.bignum_digits as u64).saturating_mul(options.bignum_reps as u64)),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "set_ops",
+        size_flag: "--set-n",
+        size: |options| options.set_n as u64,
+        set_size: |options, size| options.set_n = size as _,
+        work_unit: "element of each input set",
+        run: |options, seed| run_set_ops(options.set_n, seed),
+        estimated_ops: Some(|options| (options.set_n as u64).saturating_mul(4)),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "regex_match",
+        size_flag: "--regex-n",
+        size: |options| options.regex_n as u64,
+        set_size: |options, size| options.regex_n = size as _,
+        work_unit: "input string",
+        run: |options, seed| run_regex_match(options.regex_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "vtable_loop",
+        size_flag: "--vtable-n",
+        size: |options| options.vtable_n as u64,
+        set_size: |options, size| options.vtable_n = size as _,
+        work_unit: "dynamic call",
+        run: |options, seed| run_vtable_loop(options.vtable_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "dijkstra",
+        size_flag: "--dij-nodes",
+        size: |options| options.dij_nodes as u64,
+        set_size: |options, size| options.dij_nodes = size as _,
+        work_unit: "node, with --dij-degree drawn edges",
+        run: |options, seed| run_dijkstra(options.dij_nodes, options.dij_degree, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "horner",
+        size_flag: "--horner-degree",
+        size: |options| options.horner_degree as u64,
+        set_size: |options, size| options.horner_degree = size as _,
+        work_unit: "polynomial degree, evaluated at --horner-reps points",
+        run: |options, seed| run_horner(options.horner_degree, options.horner_reps, seed),
+        estimated_ops: Some(|options| (options.horner_degree as u64).saturating_mul(options.horner_reps as u64)),
+        checksum_tolerance: 16,
+    },
+    Kernel {
+        name: "refcount_churn",
+        size_flag: "--rc-n",
+        size: |options| options.rc_n as u64,
+        set_size: |options, size| options.rc_n = size as _,
+        work_unit: "clone or drop of a shared handle",
+        run: |options, seed| run_refcount_churn(options.rc_n, options.rc, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "matvec",
+        size_flag: "--matvec-n",
+        size: |options| options.matvec_n as u64,
+        set_size: |options, size| options.matvec_n = size as _,
+        work_unit: "matrix side; n^2 multiply-adds per --matvec-reps rep",
+        run: |options, seed| run_matvec(options.matvec_n, options.matvec_reps, seed),
+        estimated_ops: Some(|options| (options.matvec_n as u64).saturating_pow(2).saturating_mul(options.matvec_reps as u64)),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "toposort",
+        size_flag: "--topo-nodes",
+        size: |options| options.topo_nodes as u64,
+        set_size: |options, size| options.topo_nodes = size as _,
+        work_unit: "node, with --topo-edges drawn edges in total",
+        run: |options, seed| run_toposort(options.topo_nodes, options.topo_edges, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "explicit_stack_dfs",
+        size_flag: "--edfs-n",
+        size: |options| options.edfs_n as u64,
+        set_size: |options, size| options.edfs_n = size as _,
+        work_unit: "tree node",
+        run: |options, seed| run_explicit_stack_dfs(options.edfs_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "noop",
+        size_flag: "--iterations",
+        size: |options| options.iterations as u64,
+        set_size: |options, size| options.iterations = size as _,
+        work_unit: "iteration of the timing loop with no kernel work",
+        run: |_, _| run_noop(),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "merkle",
+        size_flag: "--merkle-leaves",
+        size: |options| options.merkle_leaves as u64,
+        set_size: |options, size| options.merkle_leaves = size as _,
+        work_unit: "leaf hash",
+        run: |options, seed| run_merkle(options.merkle_leaves, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "life",
+        size_flag: "--life-n",
+        size: |options| options.life_n as u64,
+        set_size: |options, size| options.life_n = size as _,
+        work_unit: "grid side; n^2 cells, advanced --life-steps generations",
+        run: |options, seed| run_life(options.life_n, options.life_steps, seed),
+        estimated_ops: Some(|options| (options.life_n as u64).saturating_pow(2).saturating_mul(options.life_steps as u64)),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "array_sum",
+        size_flag: "--array-n",
+        size: |options| options.array_n as u64,
+        set_size: |options, size| options.array_n = size as _,
+        work_unit: "array element, summed 32 times",
+        run: |options, seed| run_array_sum(options.array_n, options.bounds, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "histogram",
+        size_flag: "--hist-n",
+        size: |options| options.hist_n as u64,
+        set_size: |options, size| options.hist_n = size as _,
+        work_unit: "binned value",
+        run: |options, seed| run_histogram(options.hist_n, options.hist_buckets, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "string_build",
+        size_flag: "--strbuild-n",
+        size: |options| options.strbuild_n as u64,
+        set_size: |options, size| options.strbuild_n = size as _,
+        work_unit: "appended chunk",
+        run: |options, seed| run_string_build(options.strbuild_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "match_dispatch",
+        size_flag: "--match-n",
+        size: |options| options.match_n as u64,
+        set_size: |options, size| options.match_n = size as _,
+        work_unit: "dispatched op",
+        run: |options, seed| run_match_dispatch(options.match_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "binary_search",
+        size_flag: "--bsearch-n",
+        size: |options| options.bsearch_n as u64,
+        set_size: |options, size| options.bsearch_n = size as _,
+        work_unit: "sorted key; searched --bsearch-queries times",
+        run: |options, seed| run_binary_search(options.bsearch_n, options.bsearch_queries, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "deep_clone",
+        size_flag: "--clone-reps",
+        size: |options| options.clone_reps as u64,
+        set_size: |options, size| options.clone_reps = size as _,
+        work_unit: "clone and walk of a --clone-depth x --clone-width tree",
+        run: |options, seed| run_deep_clone(options.clone_depth, options.clone_width, options.clone_reps, seed),
+        estimated_ops: Some(|options| {
+            clone_tree_nodes(options.clone_depth, options.clone_width).saturating_mul(options.clone_reps as u64)
+        }),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "edit_distance",
+        size_flag: "--edit-n",
+        size: |options| options.edit_n as u64,
+        set_size: |options, size| options.edit_n = size as _,
+        work_unit: "sequence length; n^2 table cells",
+        run: |options, seed| run_edit_distance(options.edit_n, seed),
+        estimated_ops: Some(|options| (options.edit_n as u64).saturating_pow(2)),
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "expr_eval",
+        size_flag: "--expr-n",
+        size: |options| options.expr_n as u64,
+        set_size: |options, size| options.expr_n = size as _,
+        work_unit: "generated, parsed, and evaluated expression",
+        run: |options, seed| run_expr_eval(options.expr_n, seed),
+        estimated_ops: None,
+        checksum_tolerance: 0,
+    },
+    Kernel {
+        name: "gc_mark",
+        size_flag: "--gc-objects",
+        size: |options| options.gc_objects as u64,
+        set_size: |options, size| options.gc_objects = size as _,
+        work_unit: "object in the heap graph",
+        run: |options, seed| run_gc_mark(options.gc_objects, options.gc_refs, seed),
+        estimated_ops: Some(|options| (options.gc_objects as u64).saturating_mul(options.gc_refs as u64 + 1)),
+        checksum_tolerance: 0,
+    },
+];
+
+// (feature, detected at run time, enabled at compile time). The compiled
+// column shows what the optimizer was allowed to use, e.g. with
+// `-C target-cpu=native`; the detected column is what the host supports.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn cpu_features() -> Vec<(&'static str, bool, bool)> {
+    vec![
+        ("sse", is_x86_feature_detected!("sse"), cfg!(target_feature = "sse")),
+        ("sse2", is_x86_feature_detected!("sse2"), cfg!(target_feature = "sse2")),
+        ("sse3", is_x86_feature_detected!("sse3"), cfg!(target_feature = "sse3")),
+        ("ssse3", is_x86_feature_detected!("ssse3"), cfg!(target_feature = "ssse3")),
+        ("sse4.1", is_x86_feature_detected!("sse4.1"), cfg!(target_feature = "sse4.1")),
+        ("sse4.2", is_x86_feature_detected!("sse4.2"), cfg!(target_feature = "sse4.2")),
+        ("popcnt", is_x86_feature_detected!("popcnt"), cfg!(target_feature = "popcnt")),
+        ("avx", is_x86_feature_detected!("avx"), cfg!(target_feature = "avx")),
+        ("avx2", is_x86_feature_detected!("avx2"), cfg!(target_feature = "avx2")),
+        ("fma", is_x86_feature_detected!("fma"), cfg!(target_feature = "fma")),
+        ("bmi2", is_x86_feature_detected!("bmi2"), cfg!(target_feature = "bmi2")),
+        ("avx512f", is_x86_feature_detected!("avx512f"), cfg!(target_feature = "avx512f")),
+    ]
 }
 
-fn parse_options() -> BenchmarkOptions {
-    let mut options = BenchmarkOptions {
-        iterations: 5,
-        sum_n: 5_000_000,
-        prime_n: 30_000,
-        matrix_n: 48,
-    };
+#[cfg(target_arch = "aarch64")]
+fn cpu_features() -> Vec<(&'static str, bool, bool)> {
+    vec![
+        ("neon", std::arch::is_aarch64_feature_detected!("neon"), cfg!(target_feature = "neon")),
+        ("sve", std::arch::is_aarch64_feature_detected!("sve"), cfg!(target_feature = "sve")),
+    ]
+}
 
-    let mut args = env::args().skip(1);
-    while let Some(arg) = args.next() {
-        match arg.as_str() {
-            "--iterations" => {
-                let value = args.next().expect("Missing value for --iterations.");
-                options.iterations = value.parse().expect("Invalid value for --iterations.");
-            }
-            "--sum-n" => {
-                let value = args.next().expect("Missing value for --sum-n.");
-                options.sum_n = value.parse().expect("Invalid value for --sum-n.");
-            }
-            "--prime-n" | "--sieve-n" => {
-                let value = args.next().expect("Missing value for --prime-n.");
-                options.prime_n = value.parse().expect("Invalid value for --prime-n.");
-            }
-            "--matrix-n" => {
-                let value = args.next().expect("Missing value for --matrix-n.");
-                options.matrix_n = value.parse().expect("Invalid value for --matrix-n.");
-            }
-            _ => {
-                panic!("Unknown option '{}'.", arg);
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+fn cpu_features() -> Vec<(&'static str, bool, bool)> {
+    Vec::new()
+}
+
+fn environment_json(options: &BenchmarkOptions) -> JsonObject {
+    let features = cpu_features();
+    let detected: Vec<&str> = features
+        .iter()
+        .filter(|feature| feature.1)
+        .map(|feature| feature.0)
+        .collect();
+    let compiled: Vec<&str> = features
+        .iter()
+        .filter(|feature| feature.2)
+        .map(|feature| feature.0)
+        .collect();
+
+    let mut json = JsonObject::new();
+    json.string("os", env::consts::OS);
+    json.string("arch", env::consts::ARCH);
+    json.number(
+        "available_parallelism",
+        thread::available_parallelism().map_or(1, |count| count.get()),
+    );
+    json.string_array("cpu_features_detected", &detected);
+    json.string_array("cpu_features_compiled", &compiled);
+    json.string("rng", options.rng.name());
+    json
+}
+
+// Parses a sysfs CPU list such as `0-3,8,10-11`.
+#[cfg(target_os = "linux")]
+fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+    let mut cpus = Vec::new();
+    for range in list.split(',').filter(|range| !range.is_empty()) {
+        let (first, last): (usize, usize) = match range.split_once('-') {
+            Some((first, last)) => (first.parse().ok()?, last.parse().ok()?),
+            None => {
+                let cpu = range.parse().ok()?;
+                (cpu, cpu)
             }
-        }
+        };
+        cpus.extend(first..=last);
     }
+    Some(cpus)
+}
 
-    if options.iterations == 0 {
-        panic!("--iterations must be greater than zero.");
+// Pins the process to the CPUs of `node` and binds all later allocations to
+// its memory with set_mempolicy(MPOL_BIND). Both are inherited by threads
+// spawned afterwards, so every kernel buffer lands on the node.
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn bind_numa_node(node: usize) {
+    extern "C" {
+        fn syscall(number: i64, ...) -> i64;
+        fn sched_setaffinity(pid: i32, size: usize, mask: *const u64) -> i32;
     }
+    #[cfg(target_arch = "x86_64")]
+    const SYS_SET_MEMPOLICY: i64 = 238;
+    #[cfg(target_arch = "aarch64")]
+    const SYS_SET_MEMPOLICY: i64 = 237;
+    const MPOL_BIND: i64 = 2;
 
-    options
-}
+    let path = format!("/sys/devices/system/node/node{}/cpulist", node);
+    let cpus = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|list| parse_cpu_list(list.trim()))
+        .filter(|cpus| !cpus.is_empty());
+    let cpus = match cpus {
+        Some(cpus) if node < 64 => cpus,
+        _ => {
+            strict_warn!("NUMA node {} is not available; running without NUMA binding.", node);
+            return;
+        }
+    };
 
-fn run_sum_xor(n: u64) -> u64 {
-    let mut acc = 0u64;
-    for i in 1..=n {
-        acc = acc.wrapping_add((i ^ (i >> 3)).wrapping_add(i % 8));
+    let mut cpu_mask = vec![0u64; cpus.iter().max().map_or(0, |cpu| cpu / 64) + 1];
+    for cpu in &cpus {
+        cpu_mask[cpu / 64] |= 1 << (cpu % 64);
+    }
+    let node_mask = 1u64 << node;
+    // The kernel reads one bit fewer than `maxnode`, hence 65 for one word.
+    let (pinned, bound) = unsafe {
+        (
+            sched_setaffinity(0, cpu_mask.len() * 8, cpu_mask.as_ptr()) == 0,
+            syscall(SYS_SET_MEMPOLICY, MPOL_BIND, &node_mask as *const u64, 65i64) == 0,
+        )
+    };
+    if !pinned {
+        strict_warn!("failed to pin to the CPUs of NUMA node {}: {}", node, io::Error::last_os_error());
+    }
+    if !bound {
+        strict_warn!("failed to bind memory to NUMA node {}: {}", node, io::Error::last_os_error());
+    }
+    if pinned && bound {
+        info!("bound to NUMA node {} ({} CPUs).", node, cpus.len());
     }
-    acc
 }
 
-fn run_prime_trial(n: usize) -> u64 {
-    if n < 2 {
-        return 0;
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+fn bind_numa_node(node: usize) {
+    strict_warn!("NUMA binding is not supported on this platform; ignoring --numa-node {}.", node);
+}
+
+// Two perf_event_open counters on the calling thread, user space only: retired
+// instructions and CPU cycles. Opened disabled; `start` resets and enables
+// both around a measurement and `stop` disables them and reads the totals.
+struct PerfCounters {
+    instructions: i32,
+    cycles: i32,
+}
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod perf_sys {
+    extern "C" {
+        pub fn syscall(number: i64, ...) -> i64;
+        pub fn ioctl(fd: i32, request: u64, ...) -> i32;
+        pub fn read(fd: i32, buffer: *mut u8, count: usize) -> isize;
+        pub fn close(fd: i32) -> i32;
     }
 
-    let mut prime_count = 0u64;
-    let mut checksum = 0u64;
-    for candidate in 2..=n {
-        let mut divisor = 2usize;
-        let mut is_prime = true;
-        while divisor * divisor <= candidate {
-            if candidate % divisor == 0 {
-                is_prime = false;
-                break;
+    // The original 64-byte `struct perf_event_attr` (PERF_ATTR_SIZE_VER0);
+    // the kernel zero-extends it.
+    #[repr(C)]
+    pub struct PerfEventAttr {
+        pub kind: u32,
+        pub size: u32,
+        pub config: u64,
+        pub sample_period: u64,
+        pub sample_type: u64,
+        pub read_format: u64,
+        pub flags: u64,
+        pub wakeup_events: u32,
+        pub bp_type: u32,
+        pub config1: u64,
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    pub const SYS_PERF_EVENT_OPEN: i64 = 298;
+    #[cfg(target_arch = "aarch64")]
+    pub const SYS_PERF_EVENT_OPEN: i64 = 241;
+    pub const PERF_TYPE_HARDWARE: u32 = 0;
+    pub const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    pub const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    // `disabled`, `exclude_kernel` and `exclude_hv` in the attr bitfield.
+    pub const FLAGS: u64 = 1 | 1 << 5 | 1 << 6;
+    pub const PERF_EVENT_IOC_ENABLE: u64 = 0x2400;
+    pub const PERF_EVENT_IOC_DISABLE: u64 = 0x2401;
+    pub const PERF_EVENT_IOC_RESET: u64 = 0x2403;
+}
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+impl PerfCounters {
+    // None, after a warning, when the kernel has no PMU for us (common in
+    // VMs) or perf_event_paranoid forbids it.
+    fn open() -> Option<PerfCounters> {
+        let open = |config: u64| {
+            let attr = perf_sys::PerfEventAttr {
+                kind: perf_sys::PERF_TYPE_HARDWARE,
+                size: std::mem::size_of::<perf_sys::PerfEventAttr>() as u32,
+                config,
+                sample_period: 0,
+                sample_type: 0,
+                read_format: 0,
+                flags: perf_sys::FLAGS,
+                wakeup_events: 0,
+                bp_type: 0,
+                config1: 0,
+            };
+            let fd = unsafe {
+                perf_sys::syscall(
+                    perf_sys::SYS_PERF_EVENT_OPEN,
+                    &attr as *const perf_sys::PerfEventAttr,
+                    0i64,
+                    -1i64,
+                    -1i64,
+                    0i64,
+                )
+            };
+            if fd < 0 {
+                Err(io::Error::last_os_error())
+            } else {
+                Ok(fd as i32)
+            }
+        };
+        let opened = open(perf_sys::PERF_COUNT_HW_INSTRUCTIONS).and_then(|instructions| {
+            open(perf_sys::PERF_COUNT_HW_CPU_CYCLES)
+                .map(|cycles| PerfCounters {
+                    instructions,
+                    cycles,
+                })
+                .inspect_err(|_| unsafe {
+                    perf_sys::close(instructions);
+                })
+        });
+        match opened {
+            Ok(counters) => Some(counters),
+            Err(error) => {
+                strict_warn!("hardware perf counters are unavailable ({}); continuing without them.", error);
+                None
             }
-            divisor += 1;
         }
+    }
 
-        if !is_prime {
-            continue;
+    fn start(&self) {
+        for fd in [self.instructions, self.cycles] {
+            unsafe {
+                perf_sys::ioctl(fd, perf_sys::PERF_EVENT_IOC_RESET, 0i64);
+                perf_sys::ioctl(fd, perf_sys::PERF_EVENT_IOC_ENABLE, 0i64);
+            }
         }
+    }
 
-        prime_count += 1;
-        checksum = checksum.wrapping_add((candidate as u64).wrapping_mul((prime_count % 16) + 1));
+    fn stop(&self) -> (u64, u64) {
+        let read = |fd: i32| {
+            let mut count = 0u64;
+            let size = std::mem::size_of::<u64>();
+            let got = unsafe {
+                perf_sys::ioctl(fd, perf_sys::PERF_EVENT_IOC_DISABLE, 0i64);
+                perf_sys::read(fd, &mut count as *mut u64 as *mut u8, size)
+            };
+            if got == size as isize {
+                count
+            } else {
+                0
+            }
+        };
+        (read(self.instructions), read(self.cycles))
     }
+}
 
-    (prime_count << 32) ^ checksum
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+impl Drop for PerfCounters {
+    fn drop(&mut self) {
+        unsafe {
+            perf_sys::close(self.instructions);
+            perf_sys::close(self.cycles);
+        }
+    }
 }
 
-fn run_affine_grid(n: usize) -> u64 {
-    if n == 0 {
-        return 0;
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+impl PerfCounters {
+    fn open() -> Option<PerfCounters> {
+        strict_warn!("hardware perf counters are not supported on this platform; continuing without them.");
+        None
     }
 
-    let mut checksum = 0u64;
-    for row in 0..n {
-        for col in 0..n {
-            let mut acc = 0u64;
-            for k in 0..n {
-                let a = (((row * 131) + (k * 17) + 13) % 256) as u64;
-                let b = (((k * 19) + (col * 97) + 53) % 256) as u64;
-                acc = acc.wrapping_add(a.wrapping_mul(b));
-            }
+    fn start(&self) {}
 
-            let index = (row as u64)
-                .wrapping_mul(n as u64)
-                .wrapping_add(col as u64);
-            checksum ^= acc.wrapping_add(index.wrapping_mul(2_654_435_761u64));
+    fn stop(&self) -> (u64, u64) {
+        (self.instructions as u64, self.cycles as u64)
+    }
+}
+
+const LONG_RUN_OPS: u64 = 10_000_000_000;
+
+// Refuses to start when a super-linear kernel's parameters imply more than
+// LONG_RUN_OPS inner-loop steps over the whole run, unless --yes is given.
+fn check_estimated_cost(options: &BenchmarkOptions) {
+    let runs = (options.iterations as u64)
+        .saturating_mul(options.repeat as u64)
+        .saturating_mul(options.seed_sweep as u64);
+    let mut too_long = false;
+    for kernel in KERNELS {
+        // A kernel resized by --size-range is judged at its largest size.
+        let largest = match (options.size_range, &options.size_kernel) {
+            (Some((_, high)), Some(name)) if name == kernel.name => {
+                let mut copy = options.clone();
+                (kernel.set_size)(&mut copy, high);
+                Some(copy)
+            }
+            _ => None,
+        };
+        let estimate = match kernel.estimated_ops {
+            Some(estimate) => estimate(largest.as_ref().unwrap_or(options)).saturating_mul(runs),
+            None => continue,
+        };
+        if estimate > LONG_RUN_OPS {
+            warn!(
+                "{} is estimated at {} inner-loop operations for this run.",
+                kernel.name, estimate
+            );
+            too_long = true;
         }
     }
 
-    checksum
+    if too_long && !options.yes {
+        error!("the estimated run time is very long; pass --yes to run anyway.");
+        std::process::exit(1);
+    }
 }
 
-fn run_branch_mix(n: u64) -> u64 {
-    let mut acc = 0u64;
-    for i in 1..=n {
-        if (i % 2) == 0 {
-            acc = acc.wrapping_add(i << 1);
-        } else {
-            acc ^= i.wrapping_mul(3);
+fn main() {
+    let mut options = parse_options();
+    if let Some(shell) = options.completions {
+        write_stdout(|out| write!(out, "{}", completion_script(shell)));
+        return;
+    }
+    if options.manifest {
+        write_stdout(|out| writeln!(out, "{}", manifest_json().to_pretty_string()));
+        return;
+    }
+    if let Some(path) = &options.trend {
+        let history = match read_results(path, options.seed) {
+            Some(history) => results_since(history, options.since, path),
+            None => {
+                error!("cannot read results history from {}.", path);
+                std::process::exit(1);
+            }
+        };
+        if !write_stdout(|out| report_trend(out, &history, options.trend_threshold)) {
+            std::process::exit(1);
+        }
+        return;
+    }
+    check_estimated_cost(&options);
+    if options.self_verify {
+        self_verify();
+    }
+    if options.print_config {
+        eprintln!("{}", options.to_json().to_pretty_string());
+    }
+    if options.emit_env {
+        eprintln!("{}", environment_json(&options).to_pretty_string());
+    }
+    if let Some(node) = options.numa_node {
+        bind_numa_node(node);
+    }
+    // Opened before the columns are chosen, so a failed open just drops
+    // the counter columns instead of leaving them all zero.
+    let perf = if options.perf_counters {
+        PerfCounters::open()
+    } else {
+        None
+    };
+    options.perf_counters = perf.is_some();
+    if options.init_delay_ms > 0 {
+        info!("waiting {} ms before timing starts.", options.init_delay_ms);
+        thread::sleep(Duration::from_millis(options.init_delay_ms));
+    }
+    // Timed like the run itself: after the NUMA binding and the delay.
+    if options.fairness_check {
+        fairness_check(options.iterations);
+    }
+    let completed = match &options.resume {
+        Some(path) => completed_runs(path, &options),
+        None => HashSet::new(),
+    };
+    // Read up front so a bad --baseline path fails before the long part.
+    let baseline = options.baseline.as_ref().map(|path| match read_results(path, options.seed) {
+        Some(results) => results_since(results, options.since, path),
+        None => {
+            error!("cannot read baseline results from {}.", path);
+            std::process::exit(1);
         }
+    });
 
-        if (i % 7) == 0 {
-            acc = acc.wrapping_add(i >> 2);
+    let references = options.verify.as_ref().map(|path| match read_reference(path) {
+        Some(references) => references,
+        None => {
+            error!("cannot read reference values from {}.", path);
+            std::process::exit(1);
+        }
+    });
+
+    // CSV rows are streamed to --output as each measurement finishes, so an
+    // interrupted sweep still leaves a file that --resume can continue.
+    let columns = active_columns(&options);
+    let mut streamed = match &options.output {
+        Some(path) if options.format == OutputFormat::Csv => {
+            let (mut file, include_header) = open_output(path, &options);
+            if include_header {
+                writeln!(file, "{}", csv_header(&columns, options.csv_quote_always))
+                    .expect("Failed to write results.");
+            }
+            Some(file)
+        }
+        _ => None,
+    };
+
+    let mut samples = options.samples_out.as_ref().map(|path| open_samples(path, &options));
+
+    let plan = build_plan(&options, &completed);
+    let mut progress = Progress::new(plan.len(), &options);
+    let mut results = Vec::new();
+    info!("running {} measurements of {} iterations each.", plan.len(), options.iterations);
+    let mut convergence: Vec<Convergence> = Vec::new();
+    for (index, run) in plan.iter().enumerate() {
+        let tracked = if options.repeat_until_stable {
+            let position = convergence
+                .iter()
+                .position(|entry| entry.algorithm == run.kernel.name && entry.seed == run.seed);
+            let position = position.unwrap_or_else(|| {
+                convergence.push(Convergence {
+                    algorithm: run.kernel.name,
+                    seed: run.seed,
+                    batches: 0,
+                    sum_ms: 0.0,
+                    change_pct: 0.0,
+                    converged: false,
+                });
+                convergence.len() - 1
+            });
+            if convergence[position].converged {
+                continue;
+            }
+            Some(position)
         } else {
-            acc ^= i % 16;
+            None
+        };
+        // Outside measure_kernel, so the pause never counts towards a timing.
+        if index > 0 && options.cooldown_ms > 0 {
+            thread::sleep(Duration::from_millis(options.cooldown_ms));
+        }
+        progress.advance(run.kernel.name, run.seed, run.batch);
+        let result = measure_kernel(run, &options, perf.as_ref());
+        debug!(
+            "{} seed {} batch {}: {:.3} ms",
+            result.algorithm, result.seed, result.batch, result.total_ms
+        );
+        if options.self_audit {
+            self_audit(&result, options.self_audit_tolerance);
+        }
+        if let (Some(path), Some(references), true) = (&options.verify, &references, options.fail_fast) {
+            if !verify_result(path, references, &result, options.verify_tolerance) {
+                error!("stopping at the first mismatch because of --fail-fast.");
+                std::process::exit(1);
+            }
+        }
+        if let Some(file) = &mut samples {
+            write_samples(file, &result, columns.contains(&Column::Seed))
+                .expect("Failed to write samples.");
+        }
+        if let Some(file) = streamed.as_mut() {
+            if options.annotate {
+                for line in csv_annotations(results.last(), &result, &columns) {
+                    writeln!(file, "{}", line).expect("Failed to write results.");
+                }
+            }
+            writeln!(file, "{}", csv_row(&result, &columns, options.csv_quote_always))
+                .and_then(|_| file.flush())
+                .expect("Failed to write results.");
         }
+        if let Some(position) = tracked {
+            convergence[position].add(result.mean_ms(), options.stable_threshold);
+        }
+        results.push(result);
+    }
+    progress.finish();
 
-        if (i % 97) == 0 {
-            acc = acc.wrapping_add(i.wrapping_mul((i % 13) + 1));
+    if options.repeat_until_stable {
+        report_convergence(&convergence, &options);
+    }
+
+    if let Some(path) = &options.against_last {
+        compare_against_last(path, &results, &options);
+    }
+    if options.drift_report && !results.is_empty() {
+        report_drift(&results, options.drift_threshold);
+    }
+    if options.ramp_report && !results.is_empty() {
+        report_ramp(&results);
+    }
+    if options.profile_hot && !results.is_empty() {
+        report_hot(&results);
+    }
+    let speedups = match (&options.baseline, &baseline) {
+        (Some(path), Some(baseline)) => Some(report_speedups(&format!("Speedup against {}", path), baseline, &results)),
+        _ => None,
+    };
+    if options.geomean {
+        match &speedups {
+            Some(speedups) => match geometric_mean(speedups) {
+                Some(geomean) => eprintln!(
+                    "Geometric mean speedup: {:.3}x over {} algorithms",
+                    geomean,
+                    speedups.len()
+                ),
+                None => warn!("no algorithm is shared with the baseline; no geometric mean to report."),
+            },
+            None => {
+                let means: Vec<f64> = mean_by_algorithm(&results).into_iter().map(|entry| entry.1).collect();
+                if let Some(geomean) = geometric_mean(&means) {
+                    eprintln!("Geometric mean of mean_ms: {:.6} ms over {} algorithms", geomean, means.len());
+                }
+            }
         }
     }
 
-    acc
+    if let Some(command) = &options.compare_cmd {
+        let target = run_compare_command(command, options.seed);
+        report_speedups(
+            &format!("Speedup of '{}' relative to this run", command),
+            &results,
+            &target,
+        );
+    }
+    if let Some(path) = &options.write_reference {
+        write_reference(path, &results);
+    }
+    if let Some(dir) = &options.output_dir {
+        write_output_dir(dir, &results, &options)
+            .unwrap_or_else(|error| panic!("Failed to write '{}': {}", dir, error));
+    }
+    // Under --fail-fast every result was already checked as it came in.
+    let verified = match (&options.verify, &references) {
+        (Some(path), Some(references)) if !options.fail_fast => {
+            verify_results(path, references, &results, options.verify_tolerance)
+        }
+        _ => true,
+    };
+
+    match &options.output {
+        Some(_) if streamed.is_some() => {}
+        Some(path) => {
+            let (mut file, include_header) = open_output(path, &options);
+            write_results(&mut file, &results, &options, include_header)
+                .expect("Failed to write results.");
+        }
+        None => {
+            // The --tee copy is written first so it survives a closed pipe.
+            if let Some(path) = &options.tee {
+                let (mut file, include_header) = open_output(path, &options);
+                write_results(&mut file, &results, &options, include_header)
+                    .expect("Failed to write results.");
+            }
+            if !options.summary_line {
+                write_stdout(|out| write_results(out, &results, &options, !options.no_header));
+            }
+        }
+    }
+    // Takes the place of the stdout results; files still get the full rows.
+    if options.summary_line {
+        write_stdout(|out| writeln!(out, "{}", summary_line(&results)));
+    }
+    if !verified {
+        std::process::exit(1);
+    }
 }
 
-fn run_gcd_fold(n: usize) -> u64 {
-    let mut checksum = 0u64;
-    for i in 1..=n as u64 {
-        let mut a = i.wrapping_mul(37).wrapping_add(17);
-        let mut b = i.wrapping_mul(53).wrapping_add(19);
-        while b != 0 {
-            let t = a % b;
-            a = b;
-            b = t;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_results() -> Vec<BenchmarkResult> {
+        vec![
+            BenchmarkResult {
+                algorithm: "sum_xor".to_string(),
+                iterations: 1_000,
+                total_ms: 1_234_567.891,
+                checksum: 18_246_126_162_622_269_549,
+                seed: DEFAULT_SEED,
+                batch: 1,
+                position: 0,
+                samples_ns: Vec::new(),
+                started_ns: 0,
+                value: 0,
+                instructions: 0,
+                cycles: 0,
+                params_hash: 0,
+                size: 1_000_000,
+            },
+            BenchmarkResult {
+                algorithm: "affine_grid".to_string(),
+                iterations: 5,
+                total_ms: 0.25,
+                checksum: 42,
+                seed: DEFAULT_SEED,
+                batch: 1,
+                position: 0,
+                samples_ns: Vec::new(),
+                started_ns: 0,
+                value: 0,
+                instructions: 0,
+                cycles: 0,
+                params_hash: 0,
+                size: 64,
+            },
+        ]
+    }
+
+    #[test]
+    fn csv_output_contains_no_grouping_characters() {
+        let mut out = Vec::new();
+        write_csv(&mut out, &sample_results(), &BASE_COLUMNS, true, false, false).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        for line in text.lines().skip(1) {
+            let fields: Vec<&str> = line.split(',').collect();
+            assert_eq!(fields.len(), BASE_COLUMNS.len(), "unexpected field count in '{}'", line);
+            for field in &fields[2..] {
+                assert!(
+                    field.chars().all(|c| c.is_ascii_digit() || c == '.'),
+                    "field '{}' is not a plain number",
+                    field
+                );
+            }
         }
+        assert!(text.contains("rust,sum_xor,1000,1000000,1234567.891,1234.567891,18246126162622269549"));
+    }
 
-        checksum = checksum.wrapping_add(a.wrapping_mul((i % 16) + 1));
+    // Each output format as rows of (column, value) pairs sorted by
+    // column, undoing the format's own encoding of the values.
+    fn csv_fields(text: &str) -> Vec<Vec<(String, String)>> {
+        let mut lines = text.lines();
+        let names: Vec<&str> = lines.next().unwrap().split(',').collect();
+        lines.map(|line| sorted_fields(names.iter().copied().zip(line.split(',')))).collect()
     }
 
-    checksum
-}
+    fn tsv_fields(text: &str) -> Vec<Vec<(String, String)>> {
+        let mut lines = text.lines();
+        let names: Vec<&str> = lines.next().unwrap().split('\t').collect();
+        lines.map(|line| sorted_fields(names.iter().copied().zip(line.split('\t')))).collect()
+    }
 
-fn run_lcg_stream(n: u64) -> u64 {
-    let mut state = 123_456_789u64;
-    let mut checksum = 0u64;
-    for _ in 0..n {
-        state = (state.wrapping_mul(1_103_515_245).wrapping_add(12_345)) % 2_147_483_647;
-        if (state % 2) == 0 {
-            checksum = checksum.wrapping_add(state);
-        } else {
-            checksum ^= state;
+    fn table_fields(text: &str) -> Vec<Vec<(String, String)>> {
+        let mut lines = text.lines();
+        let names: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+        lines
+            .map(|line| sorted_fields(names.iter().copied().zip(line.split_whitespace())))
+            .collect()
+    }
+
+    fn influx_fields(text: &str) -> Vec<Vec<(String, String)>> {
+        text.lines()
+            .map(|line| {
+                let parts: Vec<&str> = line.split(' ').collect();
+                assert_eq!(parts.len(), 3, "{}", line);
+                let pairs = parts[0].split(',').skip(1).chain(parts[1].split(','));
+                let mut fields: Vec<(String, String)> = pairs
+                    .map(|pair| {
+                        let (name, value) = pair.split_once('=').unwrap();
+                        let value = match value.strip_suffix('i') {
+                            Some(integer) if name == "checksum" => {
+                                (integer.parse::<i64>().unwrap() as u64).to_string()
+                            }
+                            Some(integer) => integer.to_string(),
+                            None => value.trim_matches('"').to_string(),
+                        };
+                        (name.to_string(), value)
+                    })
+                    .collect();
+                fields.sort();
+                fields
+            })
+            .collect()
+    }
+
+    fn gnuplot_fields(text: &str) -> Vec<Vec<(String, String)>> {
+        let mut lines = text.lines();
+        let names: Vec<&str> = lines.next().unwrap().trim_start_matches("# ").split(' ').collect();
+        lines
+            .filter(|line| !line.starts_with('#'))
+            .map(|line| {
+                let mut fields = sorted_fields(names.iter().copied().zip(line.split(' ')).skip(1));
+                fields.push(("language".to_string(), "rust".to_string()));
+                fields.sort();
+                fields
+            })
+            .collect()
+    }
+
+    fn markdown_fields(text: &str) -> Vec<Vec<(String, String)>> {
+        let cells = |line: &str| -> Vec<String> {
+            line.trim_matches('|').split('|').map(|cell| cell.trim().to_string()).collect()
+        };
+        let mut lines = text.lines();
+        let names = cells(lines.next().unwrap());
+        lines
+            .skip(1)
+            .map(|line| sorted_fields(names.iter().map(String::as_str).zip(cells(line).iter().map(String::as_str))))
+            .collect()
+    }
+
+    fn json_fields(text: &str) -> Vec<Vec<(String, String)>> {
+        text.lines()
+            .map(|line| line.trim().trim_end_matches(','))
+            .filter(|line| line.starts_with('{'))
+            .map(|object| {
+                let body = object.trim_start_matches('{').trim_end_matches('}');
+                sorted_fields(body.split(',').map(|pair| {
+                    let (name, value) = pair.split_once(':').unwrap();
+                    (name.trim().trim_matches('"'), value.trim().trim_matches('"'))
+                }))
+            })
+            .collect()
+    }
+
+    fn sorted_fields<'a>(pairs: impl Iterator<Item = (&'a str, &'a str)>) -> Vec<(String, String)> {
+        let mut fields: Vec<(String, String)> =
+            pairs.map(|(name, value)| (name.to_string(), value.to_string())).collect();
+        fields.sort();
+        fields
+    }
+
+    #[test]
+    fn every_output_format_encodes_the_same_fields() {
+        let mut options = default_options();
+        options.repeat = 2;
+        options.shuffle_order = true;
+        options.per_iteration = true;
+        options.parameters_hash = true;
+        options.emit_env = true;
+        let dir = env::temp_dir().join(format!("bench_rust_formats_{}", std::process::id()));
+        write_output_dir(dir.to_str().unwrap(), &sample_results(), &options).unwrap();
+        let read = |format: OutputFormat| {
+            std::fs::read_to_string(dir.join(format!("results.{}", format.extension()))).unwrap()
+        };
+
+        let expected = csv_fields(&read(OutputFormat::Csv));
+        assert_eq!(expected.len(), sample_results().len());
+        assert_eq!(expected[0].len(), active_columns(&options).len());
+        assert_eq!(tsv_fields(&read(OutputFormat::Tsv)), expected);
+        assert_eq!(table_fields(&read(OutputFormat::Table)), expected);
+        assert_eq!(influx_fields(&read(OutputFormat::Influx)), expected);
+        assert_eq!(json_fields(&read(OutputFormat::Json)), expected);
+        assert_eq!(json_fields(&read(OutputFormat::Ndjson)), expected);
+        assert_eq!(gnuplot_fields(&read(OutputFormat::Gnuplot)), expected);
+        assert_eq!(markdown_fields(&read(OutputFormat::Markdown)), expected);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn csv_quote_always_quotes_every_field() {
+        let mut out = Vec::new();
+        write_csv(&mut out, &sample_results(), &BASE_COLUMNS, true, false, true).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 3);
+        for line in text.lines() {
+            for field in line.split(',') {
+                assert!(field.len() >= 2 && field.starts_with('"') && field.ends_with('"'), "{}", line);
+            }
         }
+        let parsed = parse_results(text.as_bytes(), "quoted", DEFAULT_SEED);
+        assert_eq!(parsed[0].checksum, sample_results()[0].checksum);
     }
 
-    checksum ^ state
-}
+    #[test]
+    fn layout_checksum_does_not_depend_on_layout() {
+        for seed in [DEFAULT_SEED, 1, 42] {
+            assert_eq!(run_layout(1_000, Layout::Soa, seed), run_layout(1_000, Layout::Aos, seed));
+        }
+    }
 
-fn mix_checksum(current: u64, value: u64, iteration: u64) -> u64 {
-    let mixed = current
-        ^ value
-            .wrapping_add(0x9e37_79b9_7f4a_7c15)
-            .wrapping_add(iteration << 6)
-            .wrapping_add(iteration >> 2);
-    mixed.rotate_left(13)
-}
+    #[test]
+    fn json_keys_follow_column_order() {
+        let result = &sample_results()[0];
+        assert_eq!(
+            result_json(result, &BASE_COLUMNS).to_compact_string(),
+            "{\"language\": \"rust\", \"algorithm\": \"sum_xor\", \"iterations\": 1000, \"size\": 1000000, \
+             \"total_ms\": 1234567.891, \"mean_ms\": 1234.567891, \"checksum\": \"18246126162622269549\"}"
+        );
+    }
 
-fn print_result(algorithm: &str, iterations: usize, total_ms: f64, checksum: u64) {
-    let mean_ms = total_ms / iterations as f64;
-    println!(
-        "rust,{},{},{:.3},{:.6},{}",
-        algorithm, iterations, total_ms, mean_ms, checksum
-    );
-}
+    #[test]
+    fn iterator_pipeline_matches_imperative_loop() {
+        for (n, seed) in [(0, DEFAULT_SEED), (1, 7), (10_000, DEFAULT_SEED)] {
+            assert_eq!(run_iterator_pipeline(n, seed), iterator_pipeline_loop(n, seed));
+        }
+    }
 
-fn main() {
-    let options = parse_options();
-    println!("language,algorithm,iterations,total_ms,mean_ms,checksum");
+    #[test]
+    fn tail_sum_matches_loop() {
+        // Test builds are unoptimized and really recurse; 200,000 frames do
+        // not fit the default test-thread stack without run_tail_sum's own.
+        for (n, seed) in [(0, DEFAULT_SEED), (1, 3), (1_000, DEFAULT_SEED), (200_000, 7)] {
+            assert_eq!(run_tail_sum(n, seed), tail_sum_loop(n, seed));
+        }
+    }
 
-    let started = Instant::now();
-    let mut sum_checksum = 0u64;
-    for i in 0..options.iterations {
-        sum_checksum = mix_checksum(sum_checksum, run_sum_xor(options.sum_n), i as u64);
+    #[test]
+    fn toposort_places_every_node_of_small_graphs() {
+        assert_eq!(run_toposort(0, 1_000, DEFAULT_SEED), 0);
+        // A single node only draws self-loops, which are skipped.
+        assert_eq!(run_toposort(1, 1_000, DEFAULT_SEED), 1);
     }
-    print_result(
-        "sum_xor",
-        options.iterations,
-        started.elapsed().as_secs_f64() * 1000.0,
-        sum_checksum,
-    );
 
-    let started = Instant::now();
-    let mut prime_checksum = 0u64;
-    for i in 0..options.iterations {
-        prime_checksum = mix_checksum(prime_checksum, run_prime_trial(options.prime_n), i as u64);
+    #[test]
+    fn explicit_stack_dfs_matches_recursive() {
+        for (n, seed) in [(0, DEFAULT_SEED), (1, 3), (2_000, DEFAULT_SEED), (2_000, 42)] {
+            let (offsets, children) = dfs_tree(n, seed);
+            let recursive = if n == 0 {
+                0
+            } else {
+                dfs_recursive(&offsets, &children, 0, 0, 0)
+            };
+            assert_eq!(run_explicit_stack_dfs(n, seed), recursive);
+        }
     }
-    print_result(
-        "prime_trial",
-        options.iterations,
-        started.elapsed().as_secs_f64() * 1000.0,
-        prime_checksum,
-    );
 
-    let started = Instant::now();
-    let mut grid_checksum = 0u64;
-    for i in 0..options.iterations {
-        grid_checksum = mix_checksum(grid_checksum, run_affine_grid(options.matrix_n), i as u64);
+    #[test]
+    fn array_sum_checksum_does_not_depend_on_bounds() {
+        for (n, seed) in [(0, DEFAULT_SEED), (1, 3), (10_000, DEFAULT_SEED), (10_000, 42)] {
+            assert_eq!(
+                run_array_sum(n, BoundsMode::Checked, seed),
+                run_array_sum(n, BoundsMode::Unchecked, seed)
+            );
+        }
     }
-    print_result(
-        "affine_grid",
-        options.iterations,
-        started.elapsed().as_secs_f64() * 1000.0,
-        grid_checksum,
-    );
 
-    let started = Instant::now();
-    let mut branch_checksum = 0u64;
-    for i in 0..options.iterations {
-        branch_checksum = mix_checksum(branch_checksum, run_branch_mix(options.sum_n), i as u64);
+    #[test]
+    fn iso8601_round_trips_through_unix_seconds() {
+        assert_eq!(unix_from_iso8601("1970-01-01"), Some(0));
+        assert_eq!(unix_from_iso8601("2000-02-29T12:34:56Z"), Some(951_827_696));
+        assert_eq!(iso8601_from_unix(951_827_696), "2000-02-29T12:34:56Z");
+        for seconds in [0, 86_399, 1_700_000_000, 4_102_444_800] {
+            assert_eq!(unix_from_iso8601(&iso8601_from_unix(seconds)), Some(seconds));
+        }
+        for invalid in ["2023-02-29", "2023-13-01", "2023-1-01", "2023-01-01T24:00:00", "1969-12-31", "yesterday"] {
+            assert_eq!(unix_from_iso8601(invalid), None, "{}", invalid);
+        }
     }
-    print_result(
-        "branch_mix",
-        options.iterations,
-        started.elapsed().as_secs_f64() * 1000.0,
-        branch_checksum,
-    );
 
-    let started = Instant::now();
-    let mut gcd_checksum = 0u64;
-    for i in 0..options.iterations {
-        gcd_checksum = mix_checksum(gcd_checksum, run_gcd_fold(options.prime_n), i as u64);
+    #[test]
+    fn expr_parser_follows_precedence_and_associativity() {
+        let eval = |text: &str| {
+            ExprParser {
+                text: text.as_bytes(),
+                position: 0,
+            }
+            .expr()
+        };
+        assert_eq!(eval("1+2*3"), 7);
+        assert_eq!(eval("(1+2)*3"), 9);
+        assert_eq!(eval("10-2-3"), 5);
+        assert_eq!(eval("2*3*4-(5+6)"), 13);
+        assert_eq!(eval("2*(3-5)"), 0u64.wrapping_sub(4));
     }
-    print_result(
-        "gcd_fold",
-        options.iterations,
-        started.elapsed().as_secs_f64() * 1000.0,
-        gcd_checksum,
-    );
 
-    let started = Instant::now();
-    let mut lcg_checksum = 0u64;
-    for i in 0..options.iterations {
-        lcg_checksum = mix_checksum(lcg_checksum, run_lcg_stream(options.sum_n), i as u64);
+    #[test]
+    fn human_numbers_group_integer_digits_only() {
+        assert_eq!(group_thousands("1234567.891"), "1,234,567.891");
+        assert_eq!(group_thousands("123"), "123");
+        assert_eq!(group_thousands("1000"), "1,000");
+        assert_eq!(group_thousands("0.250000"), "0.250000");
+    }
+
+    #[test]
+    fn output_path_extensions_pick_their_format() {
+        for format in OUTPUT_FORMATS {
+            assert_eq!(format_for_path(&format!("results.{}", format.extension())), format);
+        }
+        assert_eq!(format_for_path("results"), OutputFormat::Csv);
     }
-    print_result(
-        "lcg_stream",
-        options.iterations,
-        started.elapsed().as_secs_f64() * 1000.0,
-        lcg_checksum,
-    );
 }
@@ -78,7 +78,7 @@ COMMON_ARGS=(
 )
 
 C_OUTPUT="$("${C_BIN}" "${COMMON_ARGS[@]}")"
-RUST_OUTPUT="$("${RUST_BIN}" "${COMMON_ARGS[@]}")"
+RUST_OUTPUT="$("${RUST_BIN}" "${COMMON_ARGS[@]}" --columns language,algorithm,iterations,total_ms,mean_ms,checksum)"
 
 if [[ -n "${OAF_CLI}" ]]; then
     OAF_CMD=("${OAF_CLI}")